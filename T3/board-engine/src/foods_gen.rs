@@ -0,0 +1,42 @@
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::Hasher;
+
+/// 计算占用格子列表的哈希值。
+/// 参考实现运行在 wasm32 上，对切片求哈希时长度前缀按 4 字节的 usize 写入，
+/// 这里显式写入 u32 长度，保证原生平台得到与 board_bg.wasm 相同的结果。
+fn occupied_hash(occupied: &[(i32, i32)]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    hasher.write_u32(occupied.len() as u32);
+    for &(x, y) in occupied {
+        hasher.write_i32(x);
+        hasher.write_i32(y);
+    }
+    hasher.finish()
+}
+
+/// 在棋盘空格中生成 count 个互不重合的果子。
+/// occupied 为当前被蛇和果子占用的格子（可含重复或棋盘外坐标）；
+/// 随机数种子由 seed 与占用格子的哈希异或得到，因此同一 seed 在不同局面下产生不同的补充位置。
+/// 空格按 x 优先的顺序排列后洗牌，取前 count 个；空格不足时返回全部空格。
+pub fn generate_foods(n: i32, occupied: &[(i32, i32)], seed: u64, count: usize) -> Vec<(i32, i32)> {
+    let mut occupied = occupied.to_vec();
+    occupied.sort_unstable();
+    occupied.dedup();
+
+    let mut empty = Vec::with_capacity((n * n) as usize);
+    for x in 1..=n {
+        for y in 1..=n {
+            if occupied.binary_search(&(x, y)).is_err() {
+                empty.push((x, y));
+            }
+        }
+    }
+
+    let mut rng = StdRng::seed_from_u64(seed ^ occupied_hash(&occupied));
+    empty.shuffle(&mut rng);
+    empty.truncate(count);
+    empty
+}
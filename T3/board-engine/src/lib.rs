@@ -0,0 +1,334 @@
+use std::fmt;
+
+mod foods_gen;
+
+pub use foods_gen::generate_foods;
+
+/// 棋盘坐标 (x, y)，从 1 开始
+pub type Pos = (i32, i32);
+
+/// 参考引擎用来表示已死亡蛇体的坐标
+pub const DEAD_POS: Pos = (-1, -1);
+
+/// 蛇的移动方向，编号与 greedy_snake_step 的返回值一致（0:上, 1:左, 2:下, 3:右）
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Action {
+    Up,
+    Left,
+    Down,
+    Right,
+}
+
+impl Action {
+    pub const ALL: [Action; 4] = [Action::Up, Action::Left, Action::Down, Action::Right];
+
+    /// 方向对应的坐标增量
+    pub fn delta(self) -> (i32, i32) {
+        match self {
+            Action::Up => (0, 1),
+            Action::Left => (-1, 0),
+            Action::Down => (0, -1),
+            Action::Right => (1, 0),
+        }
+    }
+
+    /// 方向编号
+    pub fn code(self) -> i32 {
+        self as i32
+    }
+}
+
+impl TryFrom<i32> for Action {
+    type Error = EngineError;
+
+    fn try_from(code: i32) -> Result<Self, Self::Error> {
+        match code {
+            0 => Ok(Action::Up),
+            1 => Ok(Action::Left),
+            2 => Ok(Action::Down),
+            3 => Ok(Action::Right),
+            _ => Err(EngineError::InvalidAction(code)),
+        }
+    }
+}
+
+/// 引擎输入不合法时的错误
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EngineError {
+    /// 方向编号不在 0..=3 内
+    InvalidAction(i32),
+    /// 行动数量与蛇的数量不一致
+    ActionCountMismatch { snakes: usize, actions: usize },
+    /// 扁平数组长度不是每条蛇 8 个数字 / 每个果子 2 个数字的整数倍
+    MalformedInput(&'static str),
+    /// 第 i 条蛇的蛇体为空、越界或相邻两节不相连
+    MalformedSnake(usize),
+}
+
+impl fmt::Display for EngineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EngineError::InvalidAction(code) => write!(f, "invalid action {}", code),
+            EngineError::ActionCountMismatch { snakes, actions } => {
+                write!(f, "{} actions for {} snakes", actions, snakes)
+            }
+            EngineError::MalformedInput(what) => write!(f, "malformed {} array", what),
+            EngineError::MalformedSnake(index) => write!(f, "malformed body of snake {}", index),
+        }
+    }
+}
+
+impl std::error::Error for EngineError {}
+
+/// 单条蛇在一个回合结束后的状态，编号与 board_bg.wasm 的 status 数组一致
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SnakeStatus {
+    /// 撞墙、撞蛇或蛇头相撞，编号 -1
+    Dead,
+    /// 正常移动，编号 0
+    Moved,
+    /// 移动并吃到果子，编号 1
+    Ate,
+}
+
+impl SnakeStatus {
+    pub fn code(self) -> i32 {
+        match self {
+            SnakeStatus::Dead => -1,
+            SnakeStatus::Moved => 0,
+            SnakeStatus::Ate => 1,
+        }
+    }
+
+    pub fn is_alive(self) -> bool {
+        self != SnakeStatus::Dead
+    }
+}
+
+/// 一个回合的结算结果，下标与输入的蛇一一对应
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TurnOutcome {
+    pub status: Vec<SnakeStatus>,
+    /// 移动后的蛇体，死亡的蛇为 None
+    pub snakes: Vec<Option<Vec<Pos>>>,
+    /// 补充后的果子：未被吃掉的果子保持原位置，被吃掉的果子槽位依次填入新果子
+    pub foods: Vec<Pos>,
+}
+
+/// 一局游戏的完整状态，snakes 只包含仍存活的蛇
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GameState {
+    pub n: i32,
+    pub snakes: Vec<Vec<Pos>>,
+    pub foods: Vec<Pos>,
+    pub seed: u64,
+}
+
+impl GameState {
+    /// 放置蛇并生成初始果子
+    pub fn new(n: i32, snakes: Vec<Vec<Pos>>, food_count: usize, seed: u64) -> GameState {
+        let foods = initial_foods(n, &snakes, seed, food_count);
+        GameState { n, snakes, foods, seed }
+    }
+
+    /// 结算一个回合但不修改当前状态
+    pub fn step(&self, actions: &[Action]) -> Result<TurnOutcome, EngineError> {
+        process_turn(self.n, &self.snakes, actions, &self.foods, self.seed)
+    }
+
+    /// 结算一个回合并推进状态：移除死亡的蛇，更新果子
+    pub fn apply(&mut self, actions: &[Action]) -> Result<TurnOutcome, EngineError> {
+        let outcome = self.step(actions)?;
+        self.snakes = outcome.snakes.iter().flatten().cloned().collect();
+        self.foods = outcome.foods.clone();
+        Ok(outcome)
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.snakes.is_empty()
+    }
+}
+
+fn in_bounds(n: i32, (x, y): Pos) -> bool {
+    x >= 1 && y >= 1 && x <= n && y <= n
+}
+
+/// 蛇体非空，每一节都在棋盘内，且相邻两节上下左右相连
+fn is_valid_body(n: i32, body: &[Pos]) -> bool {
+    !body.is_empty()
+        && body.iter().all(|&cell| in_bounds(n, cell))
+        && body.windows(2).all(|pair| (pair[0].0 - pair[1].0).abs() + (pair[0].1 - pair[1].1).abs() == 1)
+}
+
+/// 生成游戏开始时的果子，避开所有蛇体
+pub fn initial_foods(n: i32, snakes: &[Vec<Pos>], seed: u64, count: usize) -> Vec<Pos> {
+    let occupied: Vec<Pos> = snakes.iter().flatten().copied().collect();
+    generate_foods(n, &occupied, seed, count)
+}
+
+/// 结算一个回合：
+/// 1. 所有蛇同时移动，蛇头前进一格，身体跟随，蛇不会变长；
+/// 2. 蛇头出界、与任何一节蛇身（包括其他蛇的新蛇头）重合即死亡，两蛇头相撞同归于尽；
+/// 3. 碰撞优先于吃果子，死亡的蛇不会吃掉果子；
+/// 4. 被吃掉的果子在空格中补充，保持果子总数不变。
+///
+/// 行动数量与蛇的数量不一致，或蛇体为空、越界、不相连时返回错误，不会因输入不合法而崩溃。
+pub fn process_turn(
+    n: i32,
+    snakes: &[Vec<Pos>],
+    actions: &[Action],
+    foods: &[Pos],
+    seed: u64,
+) -> Result<TurnOutcome, EngineError> {
+    if snakes.len() != actions.len() {
+        return Err(EngineError::ActionCountMismatch { snakes: snakes.len(), actions: actions.len() });
+    }
+    if let Some(index) = snakes.iter().position(|body| !is_valid_body(n, body)) {
+        return Err(EngineError::MalformedSnake(index));
+    }
+
+    // 同时移动
+    let moved: Vec<Vec<Pos>> = snakes
+        .iter()
+        .zip(actions)
+        .map(|(body, action)| {
+            let (dx, dy) = action.delta();
+            let head = (body[0].0 + dx, body[0].1 + dy);
+            let mut new_body = Vec::with_capacity(body.len());
+            new_body.push(head);
+            new_body.extend_from_slice(&body[..body.len() - 1]);
+            new_body
+        })
+        .collect();
+
+    // 碰撞判定
+    let dead: Vec<bool> = moved
+        .iter()
+        .enumerate()
+        .map(|(i, body)| {
+            let head = body[0];
+            !in_bounds(n, head)
+                || moved.iter().enumerate().any(|(j, other)| {
+                    other.iter().enumerate().any(|(k, &cell)| (i != j || k > 0) && cell == head)
+                })
+        })
+        .collect();
+
+    // 吃果子
+    let mut remaining: Vec<Option<Pos>> = foods.iter().copied().map(Some).collect();
+    let mut status = Vec::with_capacity(moved.len());
+    for (body, &is_dead) in moved.iter().zip(&dead) {
+        if is_dead {
+            status.push(SnakeStatus::Dead);
+        } else if let Some(slot) = remaining.iter_mut().find(|food| **food == Some(body[0])) {
+            *slot = None;
+            status.push(SnakeStatus::Ate);
+        } else {
+            status.push(SnakeStatus::Moved);
+        }
+    }
+
+    // 补充果子：参考引擎把死亡的蛇记为 (-1,-1) 坐标，并一同计入占用格子
+    let mut occupied: Vec<Pos> = remaining.iter().flatten().copied().collect();
+    for (body, &is_dead) in moved.iter().zip(&dead) {
+        if is_dead {
+            occupied.push(DEAD_POS);
+        } else {
+            occupied.extend_from_slice(body);
+        }
+    }
+    let missing = remaining.iter().filter(|food| food.is_none()).count();
+    let mut spawned = generate_foods(n, &occupied, seed, missing).into_iter();
+    let foods = remaining
+        .into_iter()
+        .filter_map(|food| food.or_else(|| spawned.next()))
+        .collect();
+
+    let snakes = moved
+        .into_iter()
+        .zip(&dead)
+        .map(|(body, &is_dead)| if is_dead { None } else { Some(body) })
+        .collect();
+
+    Ok(TurnOutcome { status, snakes, foods })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snake(coords: [i32; 8]) -> Vec<Pos> {
+        coords.chunks(2).map(|c| (c[0], c[1])).collect()
+    }
+
+    // 测试1：初始果子与课程组提供的 board_bg.wasm 一致（1v1 初始位置，seed = 0）
+    #[test]
+    fn test_initial_foods_match_reference() {
+        let snakes = vec![snake([1, 4, 1, 3, 1, 2, 1, 1]), snake([5, 2, 5, 3, 5, 4, 5, 5])];
+        assert_eq!(initial_foods(5, &snakes, 0, 5), vec![(3, 3), (2, 1), (2, 5), (2, 3), (3, 1)]);
+        assert_eq!(initial_foods(5, &[], 0, 3), vec![(5, 5), (1, 5), (3, 4)]);
+    }
+
+    // 测试2：蛇头相撞同归于尽，且碰撞优先，果子保留在原地
+    #[test]
+    fn test_head_on_collision_keeps_food() {
+        let snakes = vec![snake([2, 3, 1, 3, 1, 2, 1, 1]), snake([4, 3, 5, 3, 5, 2, 5, 1])];
+        let foods = vec![(3, 3), (1, 5)];
+        let outcome = process_turn(5, &snakes, &[Action::Right, Action::Left], &foods, 7).unwrap();
+        assert_eq!(outcome.status, vec![SnakeStatus::Dead, SnakeStatus::Dead]);
+        assert_eq!(outcome.snakes, vec![None, None]);
+        assert_eq!(outcome.foods, foods);
+    }
+
+    // 测试3：吃到果子不增长，果子总数守恒且不会生成在蛇或果子上
+    #[test]
+    fn test_eat_without_growth_and_respawn() {
+        let snakes = vec![snake([1, 4, 1, 3, 1, 2, 1, 1])];
+        let foods = vec![(3, 3), (1, 5)];
+        let outcome = process_turn(5, &snakes, &[Action::Up], &foods, 42).unwrap();
+        assert_eq!(outcome.status, vec![SnakeStatus::Ate]);
+        let body = outcome.snakes[0].clone().unwrap();
+        assert_eq!(body, vec![(1, 5), (1, 4), (1, 3), (1, 2)]);
+        assert_eq!(outcome.foods.len(), 2);
+        assert_eq!(outcome.foods[0], (3, 3));
+        assert!(!body.contains(&outcome.foods[1]) && outcome.foods[1] != (3, 3));
+    }
+
+    // 测试4：撞墙、撞到其他蛇移动后的身体都会死亡，追随其他蛇的尾巴则安全
+    #[test]
+    fn test_wall_and_body_collisions() {
+        let snakes = vec![
+            snake([1, 1, 2, 1, 3, 1, 4, 1]),
+            snake([2, 2, 3, 2, 4, 2, 5, 2]),
+            snake([5, 3, 5, 4, 5, 5, 4, 5]),
+            snake([3, 3, 3, 4, 3, 5, 2, 5]),
+        ];
+        let actions = [Action::Left, Action::Up, Action::Down, Action::Down];
+        let outcome = process_turn(5, &snakes, &actions, &[(1, 5)], 1).unwrap();
+        let status = vec![SnakeStatus::Dead, SnakeStatus::Moved, SnakeStatus::Moved, SnakeStatus::Dead];
+        assert_eq!(outcome.status, status);
+    }
+
+    // 测试5：行动数量或方向编号不合法时返回错误
+    #[test]
+    fn test_invalid_input() {
+        let snakes = vec![snake([1, 4, 1, 3, 1, 2, 1, 1])];
+        assert_eq!(
+            process_turn(5, &snakes, &[], &[], 0),
+            Err(EngineError::ActionCountMismatch { snakes: 1, actions: 0 })
+        );
+        assert_eq!(Action::try_from(4), Err(EngineError::InvalidAction(4)));
+    }
+
+    // 测试6：蛇体为空、越界或不相连时返回错误而不是崩溃
+    #[test]
+    fn test_malformed_snake() {
+        let good = snake([1, 4, 1, 3, 1, 2, 1, 1]);
+        let actions = [Action::Up, Action::Up];
+        assert_eq!(process_turn(5, &[good.clone(), vec![]], &actions, &[], 0), Err(EngineError::MalformedSnake(1)));
+        let outside = snake([6, 1, 5, 1, 4, 1, 3, 1]);
+        assert_eq!(process_turn(5, &[outside, good.clone()], &actions, &[], 0), Err(EngineError::MalformedSnake(0)));
+        let broken = snake([3, 3, 3, 2, 5, 2, 5, 1]);
+        assert_eq!(process_turn(5, &[good, broken], &actions, &[], 0), Err(EngineError::MalformedSnake(1)));
+    }
+}
@@ -0,0 +1,120 @@
+// Record a golden trace from the prebuilt board_bg.wasm shipped with the course.
+// Run from T3/board before regenerating pkg/ from source:
+//   node golden/gen_trace.cjs > golden/trace.txt
+//
+// Line formats (seeds are unsigned 64-bit decimals):
+//   I|n seed count|snakes|foods
+//   T|n seed|snakes|actions|foods|status|snakes_out|foods_out
+//
+// The crowded turns at the end line up many snakes that all eat at once, so the
+// food hash set deletes and regrows heavily; their foods order checks the table layout.
+const board = require('../pkg/board.js');
+
+const SEEDS = [
+  0n,
+  1n,
+  0x123456789ABCDEF0n,
+  0xDEADBEEFCAFEBABEn,
+  0x8000000000000000n,
+  0xFFFFFFFFFFFFFFFFn,
+  0x7FFFFFFFFFFFFFFFn,
+  0x0123456789ABCDEFn,
+  42n,
+  2025n,
+];
+
+const MODES = [
+  { n: 5, foods: 5, rounds: 50, snakes: [[1, 4, 1, 3, 1, 2, 1, 1], [5, 2, 5, 3, 5, 4, 5, 5]] },
+  { n: 8, foods: 10, rounds: 100, snakes: [[4, 1, 3, 1, 2, 1, 1, 1], [8, 4, 8, 3, 8, 2, 8, 1], [5, 8, 6, 8, 7, 8, 8, 8], [1, 5, 1, 6, 1, 7, 1, 8]] },
+];
+
+const DIRS = [[0, 1], [-1, 0], [0, -1], [1, 0]];
+
+// Deterministic LCG so the recorded moves never change between runs
+let state = 20250401;
+function next() {
+  state = (state * 1103515245 + 12345) & 0x7fffffff;
+  return state >> 4;
+}
+
+function isFree(n, x, y, blocked) {
+  return x >= 1 && y >= 1 && x <= n && y <= n && !blocked.has(x * 100 + y);
+}
+
+// Randomised moves that prefer open cells, with an occasional blind move to exercise deaths
+function pickAction(n, snake, blocked) {
+  if (next() % 48 === 0) {
+    return next() % 4;
+  }
+  let best = next() % 4;
+  let bestScore = -1;
+  for (let d = 0; d < 4; d++) {
+    const x = snake[0] + DIRS[d][0];
+    const y = snake[1] + DIRS[d][1];
+    if (!isFree(n, x, y, blocked)) continue;
+    const exits = DIRS.filter(([dx, dy]) => isFree(n, x + dx, y + dy, blocked)).length;
+    const score = exits * 4 + (next() % 6);
+    if (score > bestScore) {
+      bestScore = score;
+      best = d;
+    }
+  }
+  return best;
+}
+
+const memory = () => board.__wasm.memory.buffer;
+
+for (const mode of MODES) {
+  for (const seed of SEEDS) {
+    const wasmSeed = BigInt.asIntN(64, seed);
+    let snakes = mode.snakes.map(s => s.slice());
+    let foods = Array.from(board.initial_foods(mode.n, new Int32Array(snakes.flat()), wasmSeed, mode.foods));
+    console.log(`I|${mode.n} ${seed} ${mode.foods}|${snakes.flat().join(' ')}|${foods.join(' ')}`);
+
+    for (let round = 0; round < mode.rounds && snakes.length > 0; round++) {
+      const blocked = new Set();
+      snakes.forEach(s => { for (let j = 0; j < 6; j += 2) blocked.add(s[j] * 100 + s[j + 1]); });
+      const actions = snakes.map(s => pickAction(mode.n, s, blocked));
+
+      const out = board.process_turn(mode.n, new Int32Array(snakes.flat()), new Int32Array(actions), new Int32Array(foods), wasmSeed);
+      const status = Array.from(new Int32Array(memory(), out.status(), snakes.length));
+      const snakesOut = Array.from(new Int32Array(memory(), out.snakes(), snakes.length * 8));
+      const foodsOut = Array.from(new Int32Array(memory(), out.foods(), mode.foods * 2));
+      console.log(`T|${mode.n} ${seed}|${snakes.flat().join(' ')}|${actions.join(' ')}|${foods.join(' ')}|${status.join(' ')}|${snakesOut.join(' ')}|${foodsOut.join(' ')}`);
+
+      snakes = status.flatMap((s, i) => (s >= 0 ? [snakesOut.slice(i * 8, i * 8 + 8)] : []));
+      foods = foodsOut;
+    }
+  }
+}
+
+// Crowded single turns: k vertical snakes in distinct columns step up onto food
+const CROWDED = { n: 14, turns: 300 };
+for (let t = 0; t < CROWDED.turns; t++) {
+  const n = CROWDED.n;
+  const k = 6 + next() % 7;
+  const cols = [];
+  while (cols.length < k) {
+    const c = 1 + next() % n;
+    if (!cols.includes(c)) cols.push(c);
+  }
+  const snakes = cols.map(c => [c, 4, c, 3, c, 2, c, 1]);
+  const foods = cols.flatMap(c => [c, 5]);
+  const taken = new Set(cols.map(c => c * 100 + 5));
+  const total = k + 2 + next() % 6;
+  while (foods.length / 2 < total) {
+    const x = 1 + next() % n;
+    const y = 6 + next() % (n - 5);
+    if (taken.has(x * 100 + y)) continue;
+    taken.add(x * 100 + y);
+    foods.push(x, y);
+  }
+  const seed = BigInt(next());
+  const actions = snakes.map(() => 0);
+
+  const out = board.process_turn(n, new Int32Array(snakes.flat()), new Int32Array(actions), new Int32Array(foods), seed);
+  const status = Array.from(new Int32Array(memory(), out.status(), k));
+  const snakesOut = Array.from(new Int32Array(memory(), out.snakes(), k * 8));
+  const foodsOut = Array.from(new Int32Array(memory(), out.foods(), foods.length));
+  console.log(`T|${n} ${seed}|${snakes.flat().join(' ')}|${actions.join(' ')}|${foods.join(' ')}|${status.join(' ')}|${snakesOut.join(' ')}|${foodsOut.join(' ')}`);
+}
@@ -0,0 +1,312 @@
+//! 参考实现 board_bg.wasm 输出果子的顺序。
+//! 参考实现把果子放进 std 的 HashSet，结算后按哈希表的桶顺序输出，foods 数组的顺序由
+//! hashbrown 0.15 的布局与 SipHash-1-3 的密钥决定。wasm32 上没有随机数来源，密钥取自实例中
+//! 第一次创建 RandomState 时栈上与堆上的地址，之后每创建一个 RandomState，k0 加 1。
+//! 参考实现的 initial_foods 每次创建 1 个 RandomState，process_turn 每次创建 4 个，其中第一个用于果子集合。
+//! 这里按同样的规则推进密钥，并模拟 wasm32 上 hashbrown（8 字节的通用控制字节组）的插入、删除与遍历。
+//! 第一次调用是 initial_foods 的实例（snake-engine.js 与录制脚本都是如此）得到与参考实现完全相同的顺序。
+
+use board_engine::Pos;
+use std::cell::Cell;
+
+/// 实例的第一次调用为 initial_foods 时，参考实现的 k0（栈地址）
+const STACK_KEY: u64 = 1_048_415;
+/// snakes 参数为空时，参考实现的 k1（堆地址）；snakes 参数占用的堆块使它后移
+const HEAP_KEY: u64 = 1_114_120;
+
+/// 参考实现每次调用创建的 RandomState 数
+pub const INITIAL_FOODS_STATES: usize = 1;
+pub const PROCESS_TURN_STATES: usize = 4;
+
+thread_local! {
+    static KEYS: Cell<Option<(u64, u64)>> = const { Cell::new(None) };
+}
+
+/// dlmalloc 在 wasm32 上为 bytes 字节的请求分配的堆块大小；wasm-bindgen 不为空数组分配
+fn chunk_size(bytes: usize) -> u64 {
+    if bytes == 0 {
+        0
+    } else {
+        ((bytes + 4 + 7) & !7).max(16) as u64
+    }
+}
+
+/// 模拟参考实现一次调用中创建 states 个 RandomState，返回第一个的密钥。
+/// 实例中第一次创建时确定密钥，snakes_len 为这次调用的 snakes 参数的长度（i32 个数）
+pub fn next_keys(states: usize, snakes_len: usize) -> (u64, u64) {
+    KEYS.with(|cell| {
+        let (k0, k1) = cell.get().unwrap_or((STACK_KEY, HEAP_KEY + chunk_size(4 * snakes_len)));
+        cell.set(Some((k0.wrapping_add(states as u64), k1)));
+        (k0, k1)
+    })
+}
+
+/// std 的 DefaultHasher（SipHash-1-3）对 (i32, i32) 求哈希：两个 i32 按小端写入，共 8 字节
+fn hash_pos((k0, k1): (u64, u64), (x, y): Pos) -> u64 {
+    let mut v = [k0 ^ 0x736f6d6570736575, k1 ^ 0x646f72616e646f6d, k0 ^ 0x6c7967656e657261, k1 ^ 0x7465646279746573];
+    let round = |v: &mut [u64; 4]| {
+        v[0] = v[0].wrapping_add(v[1]);
+        v[1] = v[1].rotate_left(13) ^ v[0];
+        v[0] = v[0].rotate_left(32);
+        v[2] = v[2].wrapping_add(v[3]);
+        v[3] = v[3].rotate_left(16) ^ v[2];
+        v[0] = v[0].wrapping_add(v[3]);
+        v[3] = v[3].rotate_left(21) ^ v[0];
+        v[2] = v[2].wrapping_add(v[1]);
+        v[1] = v[1].rotate_left(17) ^ v[2];
+        v[2] = v[2].rotate_left(32);
+    };
+    let message = (x as u32 as u64) | ((y as u32 as u64) << 32);
+    let length = 8u64 << 56;
+    for m in [message, length] {
+        v[3] ^= m;
+        round(&mut v);
+        v[0] ^= m;
+    }
+    v[2] ^= 0xff;
+    for _ in 0..3 {
+        round(&mut v);
+    }
+    v[0] ^ v[1] ^ v[2] ^ v[3]
+}
+
+const GROUP: usize = 8;
+const EMPTY: u8 = 0xff;
+const DELETED: u8 = 0x80;
+
+fn is_special(ctrl: u8) -> bool {
+    ctrl & 0x80 != 0
+}
+
+// wasm32 上 usize 为 32 位：桶下标取哈希的低 32 位，控制字节取低 32 位中最高的 7 位
+fn h1(hash: u64) -> usize {
+    hash as u32 as usize
+}
+
+fn h2(hash: u64) -> u8 {
+    ((hash >> 25) & 0x7f) as u8
+}
+
+fn capacity_to_buckets(capacity: usize) -> usize {
+    if capacity < 4 {
+        4
+    } else if capacity < 8 {
+        8
+    } else {
+        (capacity * 8 / 7).next_power_of_two()
+    }
+}
+
+fn bucket_mask_to_capacity(mask: usize) -> usize {
+    if mask < 8 {
+        mask
+    } else {
+        (mask + 1) / 8 * 7
+    }
+}
+
+/// 与参考实现布局相同的果子集合
+pub struct FoodSet {
+    keys: (u64, u64),
+    // 桶数减一；尚未分配时为 0
+    mask: usize,
+    // 每个桶的控制字节，末尾再重复前 GROUP 个，便于按组读取
+    ctrl: Vec<u8>,
+    slots: Vec<Option<Pos>>,
+    items: usize,
+    growth_left: usize,
+}
+
+impl FoodSet {
+    /// 与 foods.iter().collect::<HashSet<_>>() 相同：按长度预留容量后依次插入
+    pub fn collect(keys: (u64, u64), foods: &[Pos]) -> FoodSet {
+        let mut set = FoodSet { keys, mask: 0, ctrl: vec![EMPTY; GROUP], slots: Vec::new(), items: 0, growth_left: 0 };
+        set.reserve(foods.len());
+        for &food in foods {
+            set.insert(food);
+        }
+        set
+    }
+
+    fn buckets(&self) -> usize {
+        self.slots.len()
+    }
+
+    fn group(&self, pos: usize) -> &[u8] {
+        &self.ctrl[pos..pos + GROUP]
+    }
+
+    fn set_ctrl(&mut self, index: usize, ctrl: u8) {
+        self.ctrl[index] = ctrl;
+        self.ctrl[(index.wrapping_sub(GROUP) & self.mask) + GROUP] = ctrl;
+    }
+
+    /// 探测序列上第一个空或已删除的桶；小表中落在末尾重复字节上时改取第 0 组中的
+    fn find_insert_slot(&self, hash: u64) -> usize {
+        let (mut pos, mut stride) = (h1(hash) & self.mask, 0);
+        loop {
+            if let Some(bit) = self.group(pos).iter().position(|&c| is_special(c)) {
+                return self.fix_insert_slot((pos + bit) & self.mask);
+            }
+            stride += GROUP;
+            pos = (pos + stride) & self.mask;
+        }
+    }
+
+    fn fix_insert_slot(&self, index: usize) -> usize {
+        if is_special(self.ctrl[index]) {
+            index
+        } else {
+            self.group(0).iter().position(|&c| is_special(c)).unwrap_or(0)
+        }
+    }
+
+    fn find(&self, food: Pos) -> Option<usize> {
+        let hash = hash_pos(self.keys, food);
+        let (mut pos, mut stride) = (h1(hash) & self.mask, 0);
+        loop {
+            let group = self.group(pos);
+            for (bit, &c) in group.iter().enumerate() {
+                let index = (pos + bit) & self.mask;
+                if c == h2(hash) && self.slots.get(index).copied().flatten() == Some(food) {
+                    return Some(index);
+                }
+            }
+            if group.contains(&EMPTY) {
+                return None;
+            }
+            stride += GROUP;
+            pos = (pos + stride) & self.mask;
+        }
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        if additional <= self.growth_left {
+            return;
+        }
+        let new_items = self.items + additional;
+        let full_capacity = bucket_mask_to_capacity(self.mask);
+        if self.buckets() > 0 && new_items <= full_capacity / 2 {
+            self.rehash_in_place();
+        } else {
+            self.resize(new_items.max(full_capacity + 1));
+        }
+    }
+
+    fn resize(&mut self, capacity: usize) {
+        let buckets = capacity_to_buckets(capacity);
+        let old: Vec<Pos> = self.iter().collect();
+        self.mask = buckets - 1;
+        self.ctrl = vec![EMPTY; buckets + GROUP];
+        self.slots = vec![None; buckets];
+        for food in old {
+            let hash = hash_pos(self.keys, food);
+            let index = self.find_insert_slot(hash);
+            self.set_ctrl(index, h2(hash));
+            self.slots[index] = Some(food);
+        }
+        self.growth_left = bucket_mask_to_capacity(self.mask) - self.items;
+    }
+
+    /// 已删除的桶太多时原地重排：所有元素先标记为已删除，再逐个移回各自探测序列上的位置
+    fn rehash_in_place(&mut self) {
+        let buckets = self.buckets();
+        for i in 0..buckets {
+            self.ctrl[i] = if is_special(self.ctrl[i]) { EMPTY } else { DELETED };
+        }
+        for i in buckets..buckets.max(GROUP) {
+            self.ctrl[i] = EMPTY;
+        }
+        let mirrored = buckets.min(GROUP);
+        self.ctrl.copy_within(0..mirrored, buckets.max(GROUP));
+        for i in 0..buckets {
+            if self.ctrl[i] != DELETED {
+                continue;
+            }
+            loop {
+                let hash = hash_pos(self.keys, self.slots[i].expect("deleted bucket holds an item"));
+                let new_i = self.find_insert_slot(hash);
+                let start = h1(hash) & self.mask;
+                let probe_index = |pos: usize| (pos.wrapping_sub(start) & self.mask) / GROUP;
+                if probe_index(i) == probe_index(new_i) {
+                    self.set_ctrl(i, h2(hash));
+                    break;
+                }
+                let previous = self.ctrl[new_i];
+                self.set_ctrl(new_i, h2(hash));
+                if previous == EMPTY {
+                    self.set_ctrl(i, EMPTY);
+                    self.slots[new_i] = self.slots[i].take();
+                    break;
+                }
+                self.slots.swap(i, new_i);
+            }
+        }
+        self.growth_left = bucket_mask_to_capacity(self.mask) - self.items;
+    }
+
+    /// 与 HashSet::extend 相同：非空时先按一半的数量（向上取整）预留容量
+    pub fn extend(&mut self, foods: &[Pos]) {
+        self.reserve(if self.items == 0 { foods.len() } else { foods.len().div_ceil(2) });
+        for &food in foods {
+            self.insert(food);
+        }
+    }
+
+    fn insert(&mut self, food: Pos) -> bool {
+        self.reserve(1);
+        let hash = hash_pos(self.keys, food);
+        let (mut pos, mut stride) = (h1(hash) & self.mask, 0);
+        let mut slot = None;
+        loop {
+            let group = self.group(pos);
+            for (bit, &c) in group.iter().enumerate() {
+                let index = (pos + bit) & self.mask;
+                if c == h2(hash) && self.slots[index] == Some(food) {
+                    return false;
+                }
+            }
+            if slot.is_none() {
+                slot = group.iter().position(|&c| is_special(c)).map(|bit| (pos + bit) & self.mask);
+            }
+            if group.contains(&EMPTY) {
+                break;
+            }
+            stride += GROUP;
+            pos = (pos + stride) & self.mask;
+        }
+        let index = self.fix_insert_slot(slot.expect("a group with an empty bucket"));
+        if self.ctrl[index] == EMPTY {
+            self.growth_left -= 1;
+        }
+        self.set_ctrl(index, h2(hash));
+        self.slots[index] = Some(food);
+        self.items += 1;
+        true
+    }
+
+    /// 删除后前后连续的非空桶不足一组时标记为空，否则标记为已删除，保证探测不会提前结束
+    pub fn remove(&mut self, food: Pos) -> bool {
+        let Some(index) = self.find(food) else {
+            return false;
+        };
+        let before = index.wrapping_sub(GROUP) & self.mask;
+        let full_before = self.group(before).iter().rev().take_while(|&&c| c != EMPTY).count();
+        let full_after = self.group(index).iter().take_while(|&&c| c != EMPTY).count();
+        let ctrl = if full_before + full_after >= GROUP {
+            DELETED
+        } else {
+            self.growth_left += 1;
+            EMPTY
+        };
+        self.set_ctrl(index, ctrl);
+        self.slots[index] = None;
+        self.items -= 1;
+        true
+    }
+
+    /// 按桶的顺序遍历，即参考实现输出的顺序
+    pub fn iter(&self) -> impl Iterator<Item = Pos> + '_ {
+        (0..self.buckets()).filter(|&i| !is_special(self.ctrl[i])).filter_map(|i| self.slots[i])
+    }
+}
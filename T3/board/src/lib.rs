@@ -0,0 +1,156 @@
+use board_engine::{Action, EngineError, Pos, SnakeStatus};
+use wasm_bindgen::prelude::*;
+
+mod food_order;
+
+use food_order::{next_keys, FoodSet, INITIAL_FOODS_STATES, PROCESS_TURN_STATES};
+
+/// process_turn 的输出缓冲区。
+/// snake-engine.js 通过 status()/snakes()/foods() 返回的指针直接读取 wasm 内存，
+/// 因此三个数组必须在缓冲区存活期间保持不动。
+#[wasm_bindgen]
+pub struct OutputBuffer {
+    status: Vec<i32>,
+    snakes: Vec<i32>,
+    foods: Vec<i32>,
+}
+
+#[wasm_bindgen]
+impl OutputBuffer {
+    /// 每条蛇的状态：-1 死亡，0 移动，1 吃到果子
+    pub fn status(&self) -> *const i32 {
+        self.status.as_ptr()
+    }
+
+    /// 每条蛇移动后的 8 个坐标，死亡的蛇全部为 -1
+    pub fn snakes(&self) -> *const i32 {
+        self.snakes.as_ptr()
+    }
+
+    /// 补充后的果子坐标
+    pub fn foods(&self) -> *const i32 {
+        self.foods.as_ptr()
+    }
+}
+
+/// 将扁平数组解析为坐标列表，chunk 为每个元素占用的数字个数
+fn parse_flat(flat: &[i32], chunk: usize, what: &'static str) -> Result<Vec<Vec<Pos>>, EngineError> {
+    if !flat.len().is_multiple_of(chunk) {
+        return Err(EngineError::MalformedInput(what));
+    }
+    Ok(flat
+        .chunks(chunk)
+        .map(|item| item.chunks(2).map(|c| (c[0], c[1])).collect())
+        .collect())
+}
+
+fn flatten(coords: &[Pos]) -> Vec<i32> {
+    coords.iter().flat_map(|&(x, y)| [x, y]).collect()
+}
+
+/// 扁平数组版本的回合结算，与 board_bg.wasm 的输入输出格式一致；
+/// 果子按参考实现的哈希表顺序输出：从输入的果子建表，删去被吃掉的，再 extend 新生成的
+fn run_turn(n: i32, snakes: &[i32], actions: &[i32], foods: &[i32], seed: u64) -> Result<OutputBuffer, EngineError> {
+    let keys = next_keys(PROCESS_TURN_STATES, snakes.len());
+    let snakes = parse_flat(snakes, 8, "snakes")?;
+    let foods: Vec<Pos> = parse_flat(foods, 2, "foods")?.into_iter().flatten().collect();
+    let actions = actions
+        .iter()
+        .map(|&code| Action::try_from(code))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let outcome = board_engine::process_turn(n, &snakes, &actions, &foods, seed)?;
+    let mut food_set = FoodSet::collect(keys, &foods);
+    for (body, status) in outcome.snakes.iter().zip(&outcome.status) {
+        if let (Some(body), SnakeStatus::Ate) = (body, status) {
+            food_set.remove(body[0]);
+        }
+    }
+    let spawned: Vec<Pos> = outcome.foods.iter().filter(|food| !foods.contains(food)).copied().collect();
+    food_set.extend(&spawned);
+    let food_order: Vec<Pos> = food_set.iter().collect();
+    Ok(OutputBuffer {
+        status: outcome.status.iter().map(|s| s.code()).collect(),
+        snakes: outcome
+            .snakes
+            .iter()
+            .flat_map(|body| match body {
+                Some(body) => flatten(body),
+                None => vec![-1; 8],
+            })
+            .collect(),
+        foods: flatten(&food_order),
+    })
+}
+
+#[wasm_bindgen]
+pub fn process_turn(n: i32, snakes: &[i32], actions: &[i32], foods: &[i32], seed: i64) -> OutputBuffer {
+    run_turn(n, snakes, actions, foods, seed as u64).unwrap_or_else(|err| wasm_bindgen::throw_str(&err.to_string()))
+}
+
+#[wasm_bindgen]
+pub fn initial_foods(n: i32, snakes: &[i32], seed: i64, required_count: i32) -> Vec<i32> {
+    next_keys(INITIAL_FOODS_STATES, snakes.len());
+    let snakes = parse_flat(snakes, 8, "snakes").unwrap_or_else(|err| wasm_bindgen::throw_str(&err.to_string()));
+    flatten(&board_engine::initial_foods(n, &snakes, seed as u64, required_count.max(0) as usize))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 由 golden/gen_trace.cjs 对课程组提供的 board_bg.wasm 录制
+    const GOLDEN_TRACE: &str = include_str!("../golden/trace.txt");
+
+    fn numbers(field: &str) -> Vec<i32> {
+        field.split_whitespace().map(|v| v.parse().unwrap()).collect()
+    }
+
+    fn header(field: &str) -> (i32, u64, Vec<&str>) {
+        let parts: Vec<&str> = field.split_whitespace().collect();
+        (parts[0].parse().unwrap(), parts[1].parse().unwrap(), parts[2..].to_vec())
+    }
+
+    // 测试1：录制的每一局初始果子完全一致
+    #[test]
+    fn test_initial_foods_match_golden_trace() {
+        let mut checked = 0;
+        for line in GOLDEN_TRACE.lines().filter(|l| l.starts_with("I|")) {
+            let fields: Vec<&str> = line.split('|').collect();
+            let (n, seed, rest) = header(fields[1]);
+            let count: i32 = rest[0].parse().unwrap();
+            assert_eq!(initial_foods(n, &numbers(fields[2]), seed as i64, count), numbers(fields[3]), "{}", line);
+            checked += 1;
+        }
+        assert!(checked > 0);
+    }
+
+    // 测试2：按录制顺序在同一实例中重放，每一回合 status、snakes、foods 都完全一致；
+    // 果子的顺序依赖实例中之前的调用次数，initial_foods 也要一并重放
+    #[test]
+    fn test_process_turn_matches_golden_trace() {
+        let mut checked = 0;
+        for line in GOLDEN_TRACE.lines() {
+            let fields: Vec<&str> = line.split('|').collect();
+            if fields[0] == "I" {
+                let (n, seed, rest) = header(fields[1]);
+                initial_foods(n, &numbers(fields[2]), seed as i64, rest[0].parse().unwrap());
+                continue;
+            }
+            let (n, seed, _) = header(fields[1]);
+            let out = run_turn(n, &numbers(fields[2]), &numbers(fields[3]), &numbers(fields[4]), seed).unwrap();
+            assert_eq!(out.status, numbers(fields[5]), "{}", line);
+            assert_eq!(out.snakes, numbers(fields[6]), "{}", line);
+            assert_eq!(out.foods, numbers(fields[7]), "{}", line);
+            checked += 1;
+        }
+        assert!(checked > 0);
+    }
+
+    // 测试3：不合法的输入返回错误而不是静默结算
+    #[test]
+    fn test_malformed_input() {
+        assert!(run_turn(5, &[1, 4, 1, 3], &[0], &[3, 3], 0).is_err());
+        assert!(run_turn(5, &[1, 4, 1, 3, 1, 2, 1, 1], &[7], &[3, 3], 0).is_err());
+    }
+}
@@ -1,13 +1,18 @@
 // Import snake decision functions
-import { greedy_snake_step as snake1 } from './t3-snake-1-T800/pkg/t3_snake_1_T800.js';
+import { SnakeSession } from './t3-snake-1-T800/pkg/t3_snake_1_T800.js';
 import { greedy_snake_step as snake2 } from './t3-snake-1-T800/pkg_x/release.js';
 import { greedy_snake_step as snake3 } from './t3-snake-1-T800/pkg_y/release.js';
-import { greedy_snake_step as snake4 } from './t3-snake-1-T800/pkg/t3_snake_1_T800.js';
 // import { greedy_snake_step as snake5 } from './t3-snake-{num}-{PL}/pkg/snake.js';
 // import { greedy_snake_step as snake6 } from './t3-snake-{num}-{PL}/pkg/snake.js';
 // import { greedy_snake_step as snake7 } from './t3-snake-{num}-{PL}/pkg/snake.js';
 // import { greedy_snake_step as snake8 } from './t3-snake-{num}-{PL}/pkg/snake.js';
 
+// Snakes 1 and 4 share the same wasm instance, so each gets its own session
+const session1 = new SnakeSession();
+const session4 = new SnakeSession();
+const snake1 = (...args) => session1.step(...args);
+const snake4 = (...args) => session4.step(...args);
+
 // Game mode
 export const GAME_MODE = "1v1"; // "1v1" or "4snakes" or "custom"
 
@@ -0,0 +1,249 @@
+//! 原生对战场：直接链接策略函数，用 board-engine 结算回合，规则与 snake-engine.js 一致。
+
+use board_engine::{Action, GameState, Pos};
+use std::time::Instant;
+
+/// 单步决策超时上限（毫秒），超时后沿用上一回合的方向
+pub const TIME_LIMIT_MS: f64 = 500.0;
+
+/// 对局参数，与 game-config.js 中的 gameParameters 对应
+#[derive(Clone, Debug)]
+pub struct ArenaConfig {
+    pub name: &'static str,
+    pub board_size: i32,
+    pub food_count: usize,
+    pub max_rounds: i32,
+    pub initial_positions: Vec<[i32; 8]>,
+}
+
+impl ArenaConfig {
+    /// 两条蛇位于对角
+    pub fn one_vs_one() -> ArenaConfig {
+        ArenaConfig {
+            name: "1v1",
+            board_size: 5,
+            food_count: 5,
+            max_rounds: 50,
+            initial_positions: vec![[1, 4, 1, 3, 1, 2, 1, 1], [5, 2, 5, 3, 5, 4, 5, 5]],
+        }
+    }
+
+    /// 四条蛇位于四个角
+    pub fn four_snakes() -> ArenaConfig {
+        ArenaConfig {
+            name: "4snakes",
+            board_size: 8,
+            food_count: 10,
+            max_rounds: 100,
+            initial_positions: vec![
+                [4, 1, 3, 1, 2, 1, 1, 1],
+                [8, 4, 8, 3, 8, 2, 8, 1],
+                [5, 8, 6, 8, 7, 8, 8, 8],
+                [1, 5, 1, 6, 1, 7, 1, 8],
+            ],
+        }
+    }
+
+    /// 八条蛇位于四角和四边中点
+    pub fn custom() -> ArenaConfig {
+        ArenaConfig {
+            name: "custom",
+            board_size: 12,
+            food_count: 20,
+            max_rounds: 200,
+            initial_positions: vec![
+                [2, 2, 2, 3, 3, 3, 4, 3],
+                [11, 2, 11, 3, 10, 3, 9, 3],
+                [2, 11, 2, 10, 3, 10, 4, 10],
+                [11, 11, 11, 10, 10, 10, 9, 10],
+                [2, 6, 3, 6, 4, 6, 5, 6],
+                [11, 6, 10, 6, 9, 6, 8, 6],
+                [6, 2, 6, 3, 6, 4, 6, 5],
+                [6, 11, 6, 10, 6, 9, 6, 8],
+            ],
+        }
+    }
+
+    /// 按 game-config.js 中的模式名称查找预设
+    pub fn preset(name: &str) -> Option<ArenaConfig> {
+        match name {
+            "1v1" => Some(ArenaConfig::one_vs_one()),
+            "4snakes" => Some(ArenaConfig::four_snakes()),
+            "custom" => Some(ArenaConfig::custom()),
+            _ => None,
+        }
+    }
+}
+
+/// 参赛者：参数与 greedy_snake_step 完全一致
+pub type Player = Box<dyn FnMut(i32, Vec<i32>, i32, Vec<i32>, i32, Vec<i32>, i32) -> i32>;
+
+/// 单局结果，下标为蛇的编号
+#[derive(Clone, Debug)]
+pub struct MatchResult {
+    pub seed: u64,
+    pub scores: Vec<i32>,
+    pub alive: Vec<bool>,
+    pub dead_round: Vec<i32>,
+    pub time_ms: Vec<f64>,
+    pub timeouts: Vec<u32>,
+}
+
+impl MatchResult {
+    /// 按得分降序、耗时升序排名，返回蛇的编号
+    pub fn ranking(&self) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..self.scores.len()).collect();
+        order.sort_by(|&a, &b| {
+            self.scores[b]
+                .cmp(&self.scores[a])
+                .then(self.time_ms[a].total_cmp(&self.time_ms[b]))
+        });
+        order
+    }
+
+    /// 与 test-2.js 的 FINAL RESULTS 输出格式一致
+    pub fn final_results(&self) -> String {
+        let mut out = String::from("=== FINAL RESULTS ===\nSnake scores:\n");
+        for i in 0..self.scores.len() {
+            let fate = if self.alive[i] {
+                " (survived)".to_string()
+            } else {
+                format!(" (died in round {})", self.dead_round[i])
+            };
+            out.push_str(&format!("Snake {}: {} points{} spent {:.3}ms\n", i + 1, self.scores[i], fate, self.time_ms[i]));
+        }
+        out
+    }
+}
+
+fn to_body(coords: &[i32; 8]) -> Vec<Pos> {
+    coords.chunks(2).map(|c| (c[0], c[1])).collect()
+}
+
+fn flatten(coords: &[Pos]) -> Vec<i32> {
+    coords.iter().flat_map(|&(x, y)| [x, y]).collect()
+}
+
+/// 超时后的默认方向：沿蛇头与第二节的方向继续前进
+fn continue_straight(body: &[Pos]) -> Action {
+    let ((hx, hy), (nx, ny)) = (body[0], body[1]);
+    if hx == nx {
+        if hy > ny { Action::Up } else { Action::Down }
+    } else if hx > nx {
+        Action::Right
+    } else {
+        Action::Left
+    }
+}
+
+/// 进行一局对战，players 的数量决定参赛蛇数（不超过预设的初始位置数）
+pub fn run_match(config: &ArenaConfig, players: &mut [Player], seed: u64) -> MatchResult {
+    let snake_num = players.len().min(config.initial_positions.len());
+    let n = config.board_size;
+    // 初始果子按预设中的全部蛇位生成，与 snake-engine.js 相同
+    let all_bodies: Vec<Vec<Pos>> = config.initial_positions.iter().map(to_body).collect();
+    let foods = board_engine::initial_foods(n, &all_bodies, seed, config.food_count);
+    let mut state = GameState {
+        n,
+        snakes: all_bodies[..snake_num].to_vec(),
+        foods,
+        seed,
+    };
+
+    let mut result = MatchResult {
+        seed,
+        scores: vec![0; snake_num],
+        alive: vec![true; snake_num],
+        dead_round: vec![config.max_rounds; snake_num],
+        time_ms: vec![0.0; snake_num],
+        timeouts: vec![0; snake_num],
+    };
+    // alive_index[i] 为当前第 i 条存活蛇的编号
+    let mut alive_index: Vec<usize> = (0..snake_num).collect();
+
+    let mut round = 0;
+    while round < config.max_rounds && !state.is_over() {
+        let foods_flat = flatten(&state.foods);
+        let mut actions = Vec::with_capacity(state.snakes.len());
+        for (i, body) in state.snakes.iter().enumerate() {
+            let others: Vec<i32> = state
+                .snakes
+                .iter()
+                .enumerate()
+                .filter(|&(j, _)| j != i)
+                .flat_map(|(_, other)| flatten(other))
+                .collect();
+            let id = alive_index[i];
+            let start = Instant::now();
+            let code = (players[id])(
+                n,
+                flatten(body),
+                state.snakes.len() as i32 - 1,
+                others,
+                config.food_count as i32,
+                foods_flat.clone(),
+                config.max_rounds - round,
+            );
+            let elapsed = start.elapsed().as_secs_f64() * 1000.0;
+            result.time_ms[id] += elapsed;
+            let action = if elapsed > TIME_LIMIT_MS {
+                result.timeouts[id] += 1;
+                continue_straight(body)
+            } else {
+                // JS 引擎遇到非法方向会中止对局，这里同样直接报错
+                Action::try_from(code).unwrap_or_else(|err| panic!("Snake {} fatal error: {}", id + 1, err))
+            };
+            actions.push(action);
+        }
+
+        let outcome = state.apply(&actions).expect("one action per snake");
+        let mut next_index = Vec::with_capacity(alive_index.len());
+        for (i, status) in outcome.status.iter().enumerate() {
+            let id = alive_index[i];
+            match status.code() {
+                -1 => {
+                    result.alive[id] = false;
+                    result.dead_round[id] = round + 1;
+                }
+                code => {
+                    result.scores[id] += code;
+                    next_index.push(id);
+                }
+            }
+        }
+        alive_index = next_index;
+        round += 1;
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn always(code: i32) -> Player {
+        Box::new(move |_, _, _, _, _, _, _| code)
+    }
+
+    // 测试1：超时回退方向与 snake-engine.js 一致
+    #[test]
+    fn test_continue_straight() {
+        assert_eq!(continue_straight(&[(1, 4), (1, 3)]), Action::Up);
+        assert_eq!(continue_straight(&[(5, 2), (5, 3)]), Action::Down);
+        assert_eq!(continue_straight(&[(3, 3), (2, 3)]), Action::Right);
+        assert_eq!(continue_straight(&[(2, 3), (3, 3)]), Action::Left);
+    }
+
+    // 测试2：死亡回合记为结算后的回合数，存活的蛇记为最大回合数
+    #[test]
+    fn test_dead_round_and_survival() {
+        // 蛇 1 向左撞墙，蛇 2 向上掉头撞到自己，都在第 1 回合死亡
+        let mut players = vec![always(1), always(0)];
+        let result = run_match(&ArenaConfig::one_vs_one(), &mut players, 0);
+        assert!(!result.alive[0]);
+        assert_eq!(result.dead_round[0], 1);
+        assert!(!result.alive[1]);
+        assert_eq!(result.dead_round[1], 1);
+        assert_eq!(result.final_results().lines().count(), 4);
+    }
+}
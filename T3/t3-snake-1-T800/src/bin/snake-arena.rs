@@ -0,0 +1,101 @@
+//! 本地对战：每条蛇各自持有一个 SnakeSession，默认全部使用 greedy 策略。
+//!
+//! 用法：snake-arena [1v1|4snakes|custom] [--seed N | --seeds A..B] [--bots a,b,...]
+//! 种子支持十进制或 0x 开头的十六进制，范围为左闭右开；
+//! --bots 按蛇的编号依次指定策略名称，数量不足时循环使用。
+
+use std::process::exit;
+use t3_snake_1_T800::arena::{run_match, ArenaConfig, Player};
+use t3_snake_1_T800::strategy::STRATEGY_NAMES;
+use t3_snake_1_T800::SnakeSession;
+
+// 未指定种子时使用的默认种子，与 game-config.js 的 CUSTOM_SEED 一致
+const CUSTOM_SEED: u64 = 0x123456789ABCDEF0;
+
+fn parse_seed(text: &str) -> Option<u64> {
+    match text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        Some(hex) => u64::from_str_radix(hex, 16).ok(),
+        None => text.parse().ok(),
+    }
+}
+
+fn usage() -> ! {
+    eprintln!("usage: snake-arena [1v1|4snakes|custom] [--seed N | --seeds A..B] [--bots a,b,...]");
+    eprintln!("bots: {}", STRATEGY_NAMES.join(", "));
+    exit(2);
+}
+
+fn make_players(count: usize, bots: &[String]) -> Vec<Player> {
+    (0..count)
+        .map(|i| {
+            let mut session = SnakeSession::with_strategy(&bots[i % bots.len()]).unwrap_or_else(|| usage());
+            Box::new(move |n, snake, snake_num, others, food_num, foods, round| {
+                session.step(n, snake, snake_num, others, food_num, foods, round)
+            }) as Player
+        })
+        .collect()
+}
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let mut config = ArenaConfig::one_vs_one();
+    let mut seeds: Option<Vec<u64>> = None;
+    let mut bots = vec!["greedy".to_string()];
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--seed" => {
+                let seed = args.next().and_then(|s| parse_seed(&s)).unwrap_or_else(|| usage());
+                seeds = Some(vec![seed]);
+            }
+            "--seeds" => {
+                let range = args.next().unwrap_or_else(|| usage());
+                let (start, end) = range.split_once("..").unwrap_or_else(|| usage());
+                let start = parse_seed(start).unwrap_or_else(|| usage());
+                let end = parse_seed(end).unwrap_or_else(|| usage());
+                seeds = Some((start..end).collect());
+            }
+            "--bots" => {
+                let list = args.next().unwrap_or_else(|| usage());
+                bots = list.split(',').map(str::to_string).collect();
+            }
+            mode => config = ArenaConfig::preset(mode).unwrap_or_else(|| usage()),
+        }
+    }
+    let seeds = seeds.unwrap_or_else(|| vec![CUSTOM_SEED]);
+
+    let snake_num = config.initial_positions.len();
+    let mut total_scores = vec![0i64; snake_num];
+    let mut survived = vec![0usize; snake_num];
+    let mut total_time = vec![0.0f64; snake_num];
+    for &seed in &seeds {
+        let mut players = make_players(snake_num, &bots);
+        let result = run_match(&config, &mut players, seed);
+        println!("Game seed: 0x{:016x}", seed);
+        print!("{}", result.final_results());
+        for i in 0..snake_num {
+            if result.timeouts[i] > 0 {
+                println!("Snake {} timed out {} times", i + 1, result.timeouts[i]);
+            }
+            total_scores[i] += result.scores[i] as i64;
+            survived[i] += result.alive[i] as usize;
+            total_time[i] += result.time_ms[i];
+        }
+        println!();
+    }
+
+    if seeds.len() > 1 {
+        println!("=== SUMMARY ({} games, mode {}) ===", seeds.len(), config.name);
+        for i in 0..snake_num {
+            println!(
+                "Snake {} ({}): avg {:.2} points, survived {}/{}, avg {:.3}ms",
+                i + 1,
+                bots[i % bots.len()],
+                total_scores[i] as f64 / seeds.len() as f64,
+                survived[i],
+                seeds.len(),
+                total_time[i] / seeds.len() as f64
+            );
+        }
+    }
+}
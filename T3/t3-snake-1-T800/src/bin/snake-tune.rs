@@ -0,0 +1,122 @@
+//! 自我对弈调参：用交叉熵方法优化加权启发式策略的参数，最佳参数写入 src/tuned.cfg，
+//! 重新编译后由 "tuned" 策略使用。
+//!
+//! 用法：snake-tune [--generations N] [--population N] [--elite N] [--games N] [--keys a,b,...]
+//!                  [--bots a,b,...] [--seed N] [--validate N] [--from-default] [--out PATH]
+//! 默认从当前嵌入的 tuned.cfg 继续优化；--validate 指定最后用于对比初始参数与结果的对局数，
+//! 验证种子与训练种子不重叠。输出文件的第一行记录完整的命令行（包括种子与调参的参数名），
+//! 对局与采样都由种子决定，在同一份代码上重新运行这一命令可以得到同样的结果。
+
+use std::process::exit;
+use t3_snake_1_T800::config::StrategyConfig;
+use t3_snake_1_T800::strategy::STRATEGY_NAMES;
+use t3_snake_1_T800::tuning::{evaluate, CrossEntropy, Fitness, TuningConfig};
+
+// 调参结果的默认输出位置，即 config.rs 嵌入的文件
+const DEFAULT_OUT: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/src/tuned.cfg");
+
+// 验证种子的起点，远离训练使用的种子
+const VALIDATION_SEED: u64 = 1_000_000;
+
+fn usage() -> ! {
+    eprintln!(
+        "usage: snake-tune [--generations N] [--population N] [--elite N] [--games N] [--keys a,b,...] \
+         [--bots a,b,...] [--seed N] [--validate N] [--from-default] [--out PATH]"
+    );
+    eprintln!("keys: {}", StrategyConfig::KEYS.join(", "));
+    eprintln!("bots: {}", STRATEGY_NAMES.join(", "));
+    exit(2);
+}
+
+fn parse<T: std::str::FromStr>(value: Option<String>) -> T {
+    value.and_then(|v| v.parse().ok()).unwrap_or_else(|| usage())
+}
+
+fn list(value: Option<String>) -> Vec<String> {
+    value.unwrap_or_else(|| usage()).split(',').map(str::to_string).collect()
+}
+
+fn describe(fitness: &Fitness) -> String {
+    format!("1v1 {:.3}, 4snakes {:.3}, total {:.3}", fitness.duel, fitness.melee, fitness.total())
+}
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let mut tuning = TuningConfig::default();
+    let mut generations = 20;
+    let mut validate = 200;
+    let mut base = StrategyConfig::tuned();
+    let mut from_default = false;
+    let mut out = DEFAULT_OUT.to_string();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--generations" => generations = parse(args.next()),
+            "--population" => tuning.population = parse(args.next()),
+            "--elite" => tuning.elite = parse(args.next()),
+            "--games" => tuning.games = parse(args.next()),
+            "--keys" => tuning.keys = list(args.next()),
+            "--bots" => tuning.references = list(args.next()),
+            "--seed" => tuning.seed = parse(args.next()),
+            "--validate" => validate = parse(args.next()),
+            "--from-default" => {
+                base = StrategyConfig::default();
+                from_default = true;
+            }
+            "--out" => out = args.next().unwrap_or_else(|| usage()),
+            _ => usage(),
+        }
+    }
+    if tuning.references.iter().any(|name| !STRATEGY_NAMES.contains(&name.as_str())) {
+        usage();
+    }
+
+    let references = tuning.references.clone();
+    // 起点为嵌入的 tuned.cfg 时，结果还依赖于运行时的那份文件
+    let command = format!(
+        "snake-tune --generations {} --population {} --elite {} --games {} --keys {} --bots {} --seed {} --validate {}{}",
+        generations,
+        tuning.population,
+        tuning.elite,
+        tuning.games,
+        tuning.keys.join(","),
+        references.join(","),
+        tuning.seed,
+        validate,
+        if from_default { " --from-default" } else { "" }
+    );
+    let mut cem = CrossEntropy::new(tuning, base.clone()).unwrap_or_else(|| usage());
+    for _ in 0..generations {
+        let seeds = cem.seeds();
+        let generation = cem.step(|config| evaluate(config, &references, &seeds));
+        println!(
+            "generation {:>3}: mean {} | best {:.3} | average {:.3}",
+            generation.index,
+            describe(&generation.center),
+            generation.best.total(),
+            generation.average
+        );
+    }
+
+    let result = cem.mean();
+    let seeds: Vec<u64> = (VALIDATION_SEED..VALIDATION_SEED + validate).collect();
+    let before = evaluate(&base, &references, &seeds);
+    let after = evaluate(&result, &references, &seeds);
+    println!("validation on {} seeds", validate);
+    println!("  initial: {}", describe(&before));
+    println!("  tuned:   {}", describe(&after));
+
+    let header = format!(
+        "# {}\n# validation on {} seeds: {} (initial {}){}\n# regenerate with the command above whenever the greedy scoring changes\n",
+        command,
+        validate,
+        describe(&after),
+        describe(&before),
+        if from_default { "" } else { ", starting from the previous tuned.cfg" }
+    );
+    if let Err(err) = std::fs::write(&out, header + &result.dump()) {
+        eprintln!("failed to write {}: {}", out, err);
+        exit(1);
+    }
+    println!("wrote {}", out);
+}
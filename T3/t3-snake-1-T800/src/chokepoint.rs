@@ -0,0 +1,207 @@
+//! 咽喉点分析：在下一回合的空格子图上运行 Tarjan 算法，求割点与点双连通分量。
+//! 去掉一个割点后，空格子图分成几块；除最大的一块以外，其余每块都是只有这一个入口的口袋。
+//! 自由空间的洪水填充分不清宽阔的区域与只靠一个格子连通的口袋，敌蛇先占住入口就能把我方封在里面。
+
+use board_engine::{Action, Pos};
+
+use crate::strategy::{step, BoardView};
+
+/// 只有一个入口的口袋
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Pocket {
+    /// 唯一的入口（割点）
+    pub entrance: Pos,
+    /// 口袋内的格子数，不包括入口
+    pub size: usize,
+}
+
+#[derive(Clone, Debug)]
+pub struct Chokepoints {
+    n: i32,
+    articulation: Vec<bool>,
+    blocks: Vec<Vec<Pos>>,
+    // 每个格子所在的最外层（最大的）口袋：(入口下标, 格子数)
+    pockets: Vec<Option<(usize, usize)>>,
+}
+
+const NONE: usize = usize::MAX;
+
+impl Chokepoints {
+    /// free[i] 为下标 i 的格子是否可以进入，下标为 (y-1)*n + (x-1)
+    pub fn new(n: i32, free: &[bool]) -> Chokepoints {
+        let cells = (n * n) as usize;
+        let neighbor = |v: usize, action: Action| {
+            let (x, y) = step(((v as i32) % n + 1, (v as i32) / n + 1), action);
+            (x >= 1 && y >= 1 && x <= n && y <= n).then(|| ((y - 1) * n + (x - 1)) as usize)
+        };
+        let mut disc = vec![NONE; cells];
+        let mut low = vec![0; cells];
+        let mut parent = vec![NONE; cells];
+        let mut size = vec![0; cells];
+        // 按发现顺序排列的格子，子树在其中是连续的一段
+        let mut order = Vec::with_capacity(cells);
+        let mut articulation = vec![false; cells];
+        let mut blocks = Vec::new();
+        let mut pockets: Vec<Option<(usize, usize)>> = vec![None; cells];
+
+        for root in 0..cells {
+            if !free[root] || disc[root] != NONE {
+                continue;
+            }
+            let start = order.len();
+            // 割点 v 与它分隔出去的子节点 u（u 的子树经过 v 才能到达其余部分）
+            let mut separations: Vec<(usize, usize)> = Vec::new();
+            let mut edges: Vec<(usize, usize)> = Vec::new();
+            let mut stack = vec![(root, 0)];
+            disc[root] = order.len();
+            low[root] = disc[root];
+            size[root] = 1;
+            order.push(root);
+            while let Some(&mut (v, ref mut next)) = stack.last_mut() {
+                if let Some(&action) = Action::ALL.get(*next) {
+                    *next += 1;
+                    let Some(u) = neighbor(v, action).filter(|&u| free[u]) else {
+                        continue;
+                    };
+                    if disc[u] == NONE {
+                        parent[u] = v;
+                        disc[u] = order.len();
+                        low[u] = disc[u];
+                        size[u] = 1;
+                        order.push(u);
+                        edges.push((v, u));
+                        stack.push((u, 0));
+                    } else if u != parent[v] && disc[u] < disc[v] {
+                        low[v] = low[v].min(disc[u]);
+                        edges.push((v, u));
+                    }
+                    continue;
+                }
+                stack.pop();
+                let Some(&(p, _)) = stack.last() else {
+                    break;
+                };
+                low[p] = low[p].min(low[v]);
+                size[p] += size[v];
+                if low[v] >= disc[p] {
+                    // 弹出到树边 (p, v) 为止的边构成一个点双连通分量
+                    let mut block = Vec::new();
+                    while let Some((a, b)) = edges.pop() {
+                        block.extend([a, b]);
+                        if (a, b) == (p, v) {
+                            break;
+                        }
+                    }
+                    block.sort_unstable();
+                    block.dedup();
+                    blocks.push(block.into_iter().map(|i| ((i as i32) % n + 1, (i as i32) / n + 1)).collect());
+                    separations.push((p, v));
+                }
+            }
+            // 孤立的格子自成一个分量
+            if order.len() == start + 1 {
+                blocks.push(vec![((root as i32) % n + 1, (root as i32) / n + 1)]);
+            }
+
+            // 根节点只有一个子树时不是割点
+            let root_children = separations.iter().filter(|&&(p, _)| p == root).count();
+            separations.retain(|&(p, _)| p != root || root_children >= 2);
+            separations.sort_unstable();
+            let component = &order[start..];
+            for group in separations.chunk_by(|a, b| a.0 == b.0) {
+                let v = group[0].0;
+                articulation[v] = true;
+                // 去掉 v 后的各块：每个被分隔的子树，以及（v 不是根时）包含父节点的其余部分
+                let mut regions: Vec<Vec<usize>> =
+                    group.iter().map(|&(_, u)| order[disc[u]..disc[u] + size[u]].to_vec()).collect();
+                if v != root {
+                    let separated = |c: usize| group.iter().any(|&(_, u)| disc[c] >= disc[u] && disc[c] < disc[u] + size[u]);
+                    regions.push(component.iter().copied().filter(|&c| c != v && !separated(c)).collect());
+                }
+                let largest = regions.iter().map(Vec::len).max().unwrap_or(0);
+                let main = regions.iter().position(|region| region.len() == largest);
+                for (i, region) in regions.iter().enumerate() {
+                    if Some(i) == main {
+                        continue;
+                    }
+                    for &c in region {
+                        if pockets[c].is_none_or(|(_, s)| region.len() > s) {
+                            pockets[c] = Some((v, region.len()));
+                        }
+                    }
+                }
+            }
+        }
+        Chokepoints { n, articulation, blocks, pockets }
+    }
+
+    /// 下一回合的空格子图：除蛇尾以外的蛇身都不能进入
+    pub fn from_view(view: &BoardView) -> Chokepoints {
+        let free: Vec<bool> = view.blocked_next_turn().iter().map(|&blocked| !blocked).collect();
+        Chokepoints::new(view.n, &free)
+    }
+
+    fn index(&self, (x, y): Pos) -> Option<usize> {
+        (x >= 1 && y >= 1 && x <= self.n && y <= self.n).then(|| ((y - 1) * self.n + (x - 1)) as usize)
+    }
+
+    /// pos 是否为割点
+    pub fn is_articulation(&self, pos: Pos) -> bool {
+        self.index(pos).is_some_and(|i| self.articulation[i])
+    }
+
+    /// 点双连通分量，每个分量为其中的格子
+    pub fn blocks(&self) -> &[Vec<Pos>] {
+        &self.blocks
+    }
+
+    /// pos 所在的最外层口袋，其入口通向最大的区域；不在任何口袋中时返回 None
+    pub fn pocket(&self, pos: Pos) -> Option<Pocket> {
+        let (entrance, size) = self.pockets[self.index(pos)?]?;
+        Some(Pocket { entrance: ((entrance as i32) % self.n + 1, (entrance as i32) / self.n + 1), size })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn free_from(rows: &[&str]) -> (i32, Vec<bool>) {
+        // rows[0] 为 y=1 的一行，'#' 为不能进入的格子
+        let n = rows.len() as i32;
+        (n, rows.iter().flat_map(|row| row.chars().map(|c| c != '#')).collect())
+    }
+
+    // 测试1：只靠一个格子连通的口袋，口袋内的格子以通向外面的格子为入口；宽阔区域中没有口袋
+    #[test]
+    fn test_pocket_behind_bottleneck() {
+        let (n, free) = free_from(&[".....", ".....", "##.##", "#...#", "#####"]);
+        let choke = Chokepoints::new(n, &free);
+        // 口袋从 (3,2) 下方的通道开始：(3,3)、(3,4) 及其两侧
+        assert!(choke.is_articulation((3, 2)) && choke.is_articulation((3, 3)) && choke.is_articulation((3, 4)));
+        assert_eq!(choke.pocket((2, 4)), Some(Pocket { entrance: (3, 2), size: 4 }));
+        assert_eq!(choke.pocket((3, 3)), Some(Pocket { entrance: (3, 2), size: 4 }));
+        assert_eq!(choke.pocket((1, 1)), None);
+        assert_eq!(choke.pocket((3, 2)), None);
+    }
+
+    // 测试2：环形通道没有割点，整个环是一个点双连通分量；死胡同内部的格子都是割点
+    #[test]
+    fn test_blocks_and_dead_end() {
+        let (n, free) = free_from(&["....", ".##.", "....", "#.##"]);
+        let choke = Chokepoints::new(n, &free);
+        // 环 + 挂在 (2,3) 下方的 (2,4)
+        assert!(choke.is_articulation((2, 3)));
+        assert!(!choke.is_articulation((1, 1)));
+        assert_eq!(choke.blocks().len(), 2);
+        assert!(choke.blocks().iter().any(|block| block.len() == 10));
+        assert_eq!(choke.pocket((2, 4)), Some(Pocket { entrance: (2, 3), size: 1 }));
+
+        let (n, free) = free_from(&["#####", "....#", "####.", "####.", "####."]);
+        let choke = Chokepoints::new(n, &free);
+        // (1,2)-(4,2) 与 (5,3)-(5,5) 互不相连
+        assert!(choke.is_articulation((2, 2)) && choke.is_articulation((3, 2)));
+        assert!(!choke.is_articulation((1, 2)) && !choke.is_articulation((4, 2)));
+        assert_eq!(choke.pocket((1, 2)), Some(Pocket { entrance: (2, 2), size: 1 }));
+    }
+}
@@ -0,0 +1,52 @@
+//! 毫秒计时：wasm 中使用宿主的 performance.now()，原生平台使用 Instant。
+
+#[cfg(target_arch = "wasm32")]
+mod imp {
+    use wasm_bindgen::prelude::*;
+
+    #[wasm_bindgen]
+    extern "C" {
+        #[wasm_bindgen(js_namespace = performance, js_name = now)]
+        fn performance_now() -> f64;
+    }
+
+    pub fn now_ms() -> f64 {
+        performance_now()
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+mod imp {
+    use std::sync::OnceLock;
+    use std::time::Instant;
+
+    static EPOCH: OnceLock<Instant> = OnceLock::new();
+
+    pub fn now_ms() -> f64 {
+        EPOCH.get_or_init(Instant::now).elapsed().as_secs_f64() * 1000.0
+    }
+}
+
+/// 单调递增的毫秒时间戳，只用于计算时间差
+pub use imp::now_ms;
+
+/// 从创建时刻开始计算的时间预算
+#[derive(Clone, Copy, Debug)]
+pub struct Deadline {
+    start: f64,
+    budget_ms: f64,
+}
+
+impl Deadline {
+    pub fn after(budget_ms: f64) -> Deadline {
+        Deadline { start: now_ms(), budget_ms }
+    }
+
+    pub fn elapsed_ms(&self) -> f64 {
+        now_ms() - self.start
+    }
+
+    pub fn expired(&self) -> bool {
+        self.elapsed_ms() >= self.budget_ms
+    }
+}
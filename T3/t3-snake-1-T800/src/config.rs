@@ -0,0 +1,183 @@
+//! 加权启发式策略的可调参数：全部权重与阈值集中在 StrategyConfig 中，
+//! 可以从 JS 按固定顺序的 Float32Array 整体设置，也可以按名称逐项设置，调参不必重新编译。
+//! snake-tune 自我对弈得到的最佳参数写入 tuned.cfg，编译时嵌入，供 "tuned" 策略使用。
+
+// snake-tune 输出的参数文件，格式与 dump 一致
+const TUNED: &str = include_str!("tuned.cfg");
+
+// 用同一份字段表生成结构体、默认值与参数名列表，保证三者顺序一致
+macro_rules! strategy_config {
+    ($($(#[doc = $doc:literal])* $field:ident: $default:expr,)*) => {
+        #[derive(Clone, Debug, PartialEq)]
+        pub struct StrategyConfig {
+            $($(#[doc = $doc])* pub $field: f32,)*
+        }
+
+        impl Default for StrategyConfig {
+            fn default() -> Self {
+                StrategyConfig { $($field: $default,)* }
+            }
+        }
+
+        impl StrategyConfig {
+            /// 参数名，顺序与 to_vec / set_all 一致
+            pub const KEYS: &'static [&'static str] = &[$(stringify!($field),)*];
+
+            /// 按 KEYS 的顺序导出全部参数
+            pub fn to_vec(&self) -> Vec<f32> {
+                vec![$(self.$field,)*]
+            }
+
+            fn field_mut(&mut self, key: &str) -> Option<&mut f32> {
+                match key {
+                    $(stringify!($field) => Some(&mut self.$field),)*
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+strategy_config! {
+    /// 果子得分的权重
+    score_weight: 10.0,
+    /// 本步吃到果子的果子得分
+    eat_bonus: 100.0,
+    /// 路线上第一个果子靠近棋盘中心（曼哈顿距离小于 center_radius）时的奖励
+    center_bonus: 10.0,
+    center_radius: 1.5,
+    /// 我方无法到达的果子的得分
+    unreachable_food_score: -20.0,
+    /// 路线上每个能赢的果子的得分
+    route_food_value: 3.0,
+    /// 果子足够多时路线最多包含的果子数
+    route_len: 3.0,
+    /// 生存得分的权重（单独一条蛇或 1v1 / 多条敌蛇）
+    survival_weight: 1.0,
+    survival_weight_melee: 10.0,
+    /// 进攻得分的权重（一般 / 恰好剩两条敌蛇时）
+    aggression_weight: 10.0,
+    aggression_weight_two_left: 3.0,
+    /// 敌蛇自由空间低于该值时视为被逼入绝境
+    free_space_threshold: 3.0,
+    /// 敌蛇蛇头与我方新头的路径距离不超过该值时视为交锋，用于进攻与领先时的远离
+    contested_radius: 2.0,
+    /// 同归于尽的奖励（1v1 / 多条敌蛇）
+    kamikaze_bonus: 1000.0,
+    kamikaze_bonus_melee: 100.0,
+    /// 走进必然被敌蛇蛇头进入的格子时的扣分，实际扣分按进入概率折算
+    head_on_cost: 500.0,
+    /// 相撞扣分的权重（领先 / 持平 / 落后且时间不多）
+    head_on_weight_cautious: 2.0,
+    head_on_weight_neutral: 1.0,
+    head_on_weight_reckless: 0.5,
+    /// 领先时每个交锋距离内的敌蛇蛇头按距离扣分
+    head_proximity_cost: 20.0,
+    /// 领地得分的权重（小棋盘 / 大棋盘）
+    territory_weight_small: 10.0,
+    territory_weight_large: 5.0,
+    /// 领地内每个果子折算的格子数
+    territory_food_value: 5.0,
+    /// 同时到达的果子按到达回合的扣分（领先时 / 多条敌蛇时）
+    tied_penalty_leading: 1.0,
+    tied_penalty_melee: 3.0,
+    /// 走进敌蛇能先到达唯一入口的口袋时的扣分，口袋越小扣分越多，不小于 pocket_size 个格子的口袋不扣分
+    pocket_cost: 100.0,
+    pocket_size: 8.0,
+}
+
+impl StrategyConfig {
+    /// 按 KEYS 的顺序整体设置，长度不符时不做任何修改并返回 false
+    pub fn set_all(&mut self, values: &[f32]) -> bool {
+        if values.len() != Self::KEYS.len() {
+            return false;
+        }
+        for (key, &value) in Self::KEYS.iter().zip(values) {
+            self.set(key, value);
+        }
+        true
+    }
+
+    /// 按名称设置单个参数，名称不存在时返回 false
+    pub fn set(&mut self, key: &str, value: f32) -> bool {
+        match self.field_mut(key) {
+            Some(field) => {
+                *field = value;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// 解析 "key=value" 形式的参数列表（以逗号、分号或换行分隔），格式与 dump 的输出一致，
+    /// # 之后到行尾为注释；任意一项无法解析时不做任何修改并返回 false
+    pub fn set_pairs(&mut self, pairs: &str) -> bool {
+        let mut updated = self.clone();
+        // # 之后到行尾为注释
+        let entries = pairs.lines().flat_map(|line| line.split('#').next().unwrap_or("").split([',', ';']));
+        for pair in entries.map(str::trim).filter(|p| !p.is_empty()) {
+            let Some((key, value)) = pair.split_once('=') else {
+                return false;
+            };
+            let Ok(value) = value.trim().parse::<f32>() else {
+                return false;
+            };
+            if !updated.set(key.trim(), value) {
+                return false;
+            }
+        }
+        *self = updated;
+        true
+    }
+
+    /// 嵌入的 tuned.cfg 中的参数，文件中没有列出的参数取默认值
+    pub fn tuned() -> StrategyConfig {
+        let mut config = StrategyConfig::default();
+        config.set_pairs(TUNED);
+        config
+    }
+
+    /// 每行一个 "key=value"，按 KEYS 的顺序
+    pub fn dump(&self) -> String {
+        Self::KEYS.iter().zip(self.to_vec()).map(|(key, value)| format!("{}={}\n", key, value)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 测试1：整体导出再导入得到相同的参数，长度不符时拒绝
+    #[test]
+    fn test_round_trip_values() {
+        let mut config = StrategyConfig::default();
+        let mut values = config.to_vec();
+        assert_eq!(values.len(), StrategyConfig::KEYS.len());
+        assert_eq!(values[0], 10.0);
+        values[0] = 7.0;
+        assert!(config.set_all(&values));
+        assert_eq!(config.score_weight, 7.0);
+        assert!(!config.set_all(&values[1..]));
+        assert_eq!(config.to_vec(), values);
+    }
+
+    // 测试2：按名称设置；dump 的输出可以原样读回，任意一项出错时整体不生效
+    #[test]
+    fn test_pairs() {
+        let mut config = StrategyConfig::default();
+        assert!(config.set("kamikaze_bonus", 500.0));
+        assert!(!config.set("no_such_key", 1.0));
+        assert!(config.set_pairs("aggression_weight=3, free_space_threshold = 4"));
+        assert_eq!((config.aggression_weight, config.free_space_threshold), (3.0, 4.0));
+
+        let mut copy = StrategyConfig::default();
+        assert!(copy.set_pairs(&config.dump()));
+        assert_eq!(copy, config);
+
+        assert!(copy.set_pairs("# header, with commas\nscore_weight=10 # trailing"));
+        assert!(!copy.set_pairs("score_weight=1,route_len=x"));
+        assert_eq!(copy.score_weight, 10.0);
+        // 嵌入的参数文件必须能完整解析
+        assert!(StrategyConfig::default().set_pairs(TUNED));
+    }
+}
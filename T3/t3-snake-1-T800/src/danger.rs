@@ -0,0 +1,112 @@
+//! 蛇头相撞风险层：估计每个格子下一回合被敌蛇蛇头进入的概率。
+//! 与 build_danger_map 标记的静态蛇身不同，这里的风险只在双方同时进入同一格时才会致命。
+
+use board_engine::Action;
+
+use crate::strategy::{step, BoardView};
+
+/// 敌蛇行为模型：给出敌蛇在各个合法方向上的概率
+pub trait EnemyMoveModel {
+    /// enemy 为 view.others 中的下标，legal 非空；返回值与 legal 一一对应，和为 1
+    fn move_probabilities(&self, view: &BoardView, enemy: usize, legal: &[Action]) -> Vec<f32>;
+}
+
+/// 在合法方向中均匀选择
+pub struct UniformModel;
+
+impl EnemyMoveModel for UniformModel {
+    fn move_probabilities(&self, _view: &BoardView, _enemy: usize, legal: &[Action]) -> Vec<f32> {
+        vec![1.0 / legal.len() as f32; legal.len()]
+    }
+}
+
+/// 偏向靠近最近果子的方向：靠近果子的方向权重为 bias，其余为 1
+pub struct FoodSeekingModel {
+    pub bias: f32,
+}
+
+impl EnemyMoveModel for FoodSeekingModel {
+    fn move_probabilities(&self, view: &BoardView, enemy: usize, legal: &[Action]) -> Vec<f32> {
+        let head = view.others[enemy][0];
+        let dist = |(x, y): (i32, i32)| view.foods.iter().map(|&(fx, fy)| (fx - x).abs() + (fy - y).abs()).min();
+        let weights: Vec<f32> = legal
+            .iter()
+            .map(|&action| match (dist(step(head, action)), dist(head)) {
+                (Some(after), Some(before)) if after < before => self.bias,
+                _ => 1.0,
+            })
+            .collect();
+        let total: f32 = weights.iter().sum();
+        weights.iter().map(|w| w / total).collect()
+    }
+}
+
+/// 敌蛇的合法方向：不出界，不撞上下一回合仍被占用的蛇身（蛇尾会腾出）
+pub fn enemy_legal_moves(view: &BoardView, enemy: usize, blocked: &[bool]) -> Vec<Action> {
+    let head = view.others[enemy][0];
+    Action::ALL
+        .into_iter()
+        .filter(|&action| {
+            let next = step(head, action);
+            view.in_bounds(next) && !blocked[view.index(next)]
+        })
+        .collect()
+}
+
+/// 每个格子下一回合至少有一条敌蛇蛇头进入的概率，model 为 None 时按均匀分布。
+/// 各敌蛇相互独立：P = 1 - Π(1 - p_i)。
+pub fn head_on_risk(view: &BoardView, model: Option<&dyn EnemyMoveModel>) -> Vec<f32> {
+    let model = model.unwrap_or(&UniformModel);
+    let blocked = view.blocked_next_turn();
+    let mut safe = vec![1.0f32; (view.n * view.n) as usize];
+    for (enemy, body) in view.others.iter().enumerate() {
+        if body.is_empty() {
+            continue;
+        }
+        let legal = enemy_legal_moves(view, enemy, &blocked);
+        if legal.is_empty() {
+            continue;
+        }
+        let probs = model.move_probabilities(view, enemy, &legal);
+        for (&action, &p) in legal.iter().zip(&probs) {
+            let idx = view.index(step(body[0], action));
+            safe[idx] *= 1.0 - p;
+        }
+    }
+    safe.iter().map(|s| 1.0 - s).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 测试1：均匀模型下，敌蛇三个合法方向各 1/3，两条敌蛇都能到达的格子风险叠加
+    #[test]
+    fn test_uniform_risk() {
+        let view = BoardView::parse(
+            5,
+            &[1, 1, 2, 1, 3, 1, 4, 1],
+            2,
+            &[2, 3, 1, 3, 1, 4, 1, 5, 4, 3, 5, 3, 5, 4, 5, 5],
+            &[],
+            50,
+        );
+        let risk = head_on_risk(&view, None);
+        // (2,3) 向上、向右、向下都可走
+        assert!((risk[view.index((2, 2))] - 1.0 / 3.0).abs() < 1e-6);
+        // (3,3) 两条敌蛇都可能进入
+        let both = 1.0 - (2.0f32 / 3.0) * (2.0 / 3.0);
+        assert!((risk[view.index((3, 3))] - both).abs() < 1e-6);
+        assert_eq!(risk[view.index((1, 1))], 0.0);
+    }
+
+    // 测试2：偏向果子的模型提高靠近果子方向的风险
+    #[test]
+    fn test_food_seeking_model() {
+        let view = BoardView::parse(5, &[1, 1, 2, 1, 3, 1, 4, 1], 1, &[2, 3, 1, 3, 1, 4, 1, 5], &[4, 3], 50);
+        let model = FoodSeekingModel { bias: 4.0 };
+        let risk = head_on_risk(&view, Some(&model));
+        assert!(risk[view.index((3, 3))] > risk[view.index((2, 2))]);
+        assert!((risk.iter().sum::<f32>() - 1.0).abs() < 1e-6);
+    }
+}
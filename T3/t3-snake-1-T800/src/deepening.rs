@@ -0,0 +1,264 @@
+//! 迭代加深：先算好一步贪心的答案作为保底，再逐层加深搜索。
+//! 软截止时间到达后不再开始新的一层；硬截止时间到达时放弃正在进行的一层，
+//! 返回最后一个完整完成的深度的结果，保证不会被引擎按超时处理。
+
+use board_engine::{Action, Pos};
+
+use crate::clock::Deadline;
+use crate::config::StrategyConfig;
+use crate::greedy::GreedyStrategy;
+use crate::opponent::OpponentModels;
+use crate::scoreboard::Scoreboard;
+use crate::strategy::{step, BoardView, SnakeStrategy};
+use crate::tracker::EnemyTracker;
+
+/// 可按深度逐层调用的搜索
+pub trait DepthLimitedSearch {
+    /// 对应的策略名称
+    fn name(&self) -> &'static str;
+
+    /// 完成 depth 层搜索后返回 Some((最佳方向, 评分))；超过 deadline 时返回 None，本层结果作废
+    fn search(&mut self, view: &BoardView, depth: usize, deadline: &Deadline) -> Option<(Action, f64)>;
+
+    /// 本回合记忆中已知的敌蛇走法（与 view.others 下标一致），搜索第一步时固定这些敌蛇的应对；默认忽略
+    fn plan_against(&mut self, _known: &[Option<Action>]) {}
+}
+
+#[derive(Clone, Debug)]
+pub struct DeepeningConfig {
+    /// 软截止（毫秒）：超过后不再开始新的一层
+    pub soft_budget_ms: f64,
+    /// 硬截止（毫秒）：超过后立即放弃当前层，必须小于引擎的 500ms 限时
+    pub hard_budget_ms: f64,
+    pub max_depth: usize,
+}
+
+impl Default for DeepeningConfig {
+    fn default() -> Self {
+        DeepeningConfig { soft_budget_ms: 80.0, hard_budget_ms: 250.0, max_depth: 16 }
+    }
+}
+
+/// 迭代加深策略：保底答案来自加权启发式策略，搜索结果可用时覆盖它
+pub struct IterativeDeepening<S> {
+    config: DeepeningConfig,
+    fallback: GreedyStrategy,
+    search: S,
+    // 上一回合完整完成的最大深度，便于调试与测试
+    completed_depth: usize,
+}
+
+impl<S: DepthLimitedSearch> IterativeDeepening<S> {
+    pub fn new(config: DeepeningConfig, search: S) -> IterativeDeepening<S> {
+        IterativeDeepening { config, fallback: GreedyStrategy::default(), search, completed_depth: 0 }
+    }
+
+    pub fn completed_depth(&self) -> usize {
+        self.completed_depth
+    }
+}
+
+impl<S: DepthLimitedSearch> SnakeStrategy for IterativeDeepening<S> {
+    fn name(&self) -> &'static str {
+        self.search.name()
+    }
+
+    fn choose(&mut self, view: &BoardView) -> Action {
+        let soft = Deadline::after(self.config.soft_budget_ms);
+        let hard = Deadline::after(self.config.hard_budget_ms);
+        // 保底答案每回合都要计算，贪心策略的跨回合记忆也随之更新
+        let mut best = self.fallback.choose(view);
+        self.search.plan_against(self.fallback.known_replies());
+        self.completed_depth = 0;
+        // 不需要看到游戏结束之后
+        let max_depth = self.config.max_depth.min(view.round.max(1) as usize);
+        for depth in 1..=max_depth {
+            if soft.expired() {
+                break;
+            }
+            match self.search.search(view, depth, &hard) {
+                Some((action, _)) => {
+                    best = action;
+                    self.completed_depth = depth;
+                }
+                None => break,
+            }
+        }
+        best
+    }
+
+    fn reset(&mut self) {
+        self.fallback.reset();
+        self.completed_depth = 0;
+    }
+
+    // 可调参数作用于保底的加权启发式策略
+    fn config(&self) -> Option<&StrategyConfig> {
+        self.fallback.config()
+    }
+
+    fn config_mut(&mut self) -> Option<&mut StrategyConfig> {
+        self.fallback.config_mut()
+    }
+
+    fn tracker(&self) -> Option<&EnemyTracker> {
+        self.fallback.tracker()
+    }
+
+    fn scoreboard(&self) -> Option<&Scoreboard> {
+        self.fallback.scoreboard()
+    }
+
+    fn opponents(&self) -> Option<&OpponentModels> {
+        self.fallback.opponents()
+    }
+}
+
+// 评分：死亡为负无穷附近，吃到果子与剩余空间越多越好
+const DEATH: f64 = -1e6;
+const FOOD_VALUE: f64 = 10.0;
+// 第一步走到敌蛇蛇头的邻格有相撞风险
+const HEAD_ON_PENALTY: f64 = 50.0;
+
+/// 只展开我方走法的生存前瞻：敌蛇按蛇尾逐回合腾出的方式收缩，叶子按可活动空间评分；
+/// 已知走法的敌蛇第一步只会进入一个格子，走到那里必然相撞，其余邻格没有风险
+#[derive(Default)]
+pub struct SurvivalLookahead {
+    known: Vec<Option<Action>>,
+}
+
+impl SurvivalLookahead {
+    /// 从第 ply 回合的蛇体 body 出发继续搜索到 depth 层，返回最佳方向与评分
+    fn dfs(
+        &self,
+        view: &BoardView,
+        body: &mut Vec<Pos>,
+        foods: &mut Vec<Pos>,
+        ply: usize,
+        depth: usize,
+        deadline: &Deadline,
+    ) -> Option<(Action, f64)> {
+        if deadline.expired() {
+            return None;
+        }
+        if ply == depth {
+            let blocked = occupied_at(view, &body[1..], ply);
+            return Some((Action::Up, view.flood_fill(body[0], &blocked) as f64));
+        }
+        // 移动后我方旧蛇尾腾出，新蛇头不能落在其余蛇身上
+        let blocked = occupied_at(view, &body[..body.len() - 1], ply + 1);
+        let mut best: Option<(Action, f64)> = None;
+        for action in Action::ALL {
+            let next = step(body[0], action);
+            if !view.in_bounds(next) || blocked[view.index(next)] {
+                continue;
+            }
+            let tail = body.pop().unwrap();
+            body.insert(0, next);
+            let eaten = foods.iter().position(|&f| f == next).map(|i| foods.swap_remove(i));
+            let result = self.dfs(view, body, foods, ply + 1, depth, deadline);
+            if let Some(food) = eaten {
+                foods.push(food);
+            }
+            body.remove(0);
+            body.push(tail);
+
+            // 越早吃到果子越好
+            let mut value = result?.1 + if eaten.is_some() { FOOD_VALUE * (depth - ply) as f64 } else { 0.0 };
+            if ply == 0 {
+                for (i, other) in view.others.iter().enumerate().filter(|(_, other)| !other.is_empty()) {
+                    match self.known.get(i).copied().flatten() {
+                        Some(reply) if step(other[0], reply) == next => value = DEATH,
+                        Some(_) => {}
+                        None if manhattan(other[0], next) == 1 => value -= HEAD_ON_PENALTY,
+                        None => {}
+                    }
+                }
+            }
+            if best.is_none_or(|(_, b)| value > b) {
+                best = Some((action, value));
+            }
+        }
+        Some(best.unwrap_or((Action::Up, DEATH)))
+    }
+}
+
+fn manhattan(a: Pos, b: Pos) -> i32 {
+    (a.0 - b.0).abs() + (a.1 - b.1).abs()
+}
+
+/// 第 ply 回合被占用的格子：敌蛇蛇体从蛇尾起腾出 ply 格，再加上我方的 cells
+fn occupied_at(view: &BoardView, cells: &[Pos], ply: usize) -> Vec<bool> {
+    let mut blocked = vec![false; (view.n * view.n) as usize];
+    for other in view.alive_others() {
+        let keep = other.len().saturating_sub(ply);
+        for &cell in &other[..keep] {
+            blocked[view.index(cell)] = true;
+        }
+    }
+    for &cell in cells {
+        blocked[view.index(cell)] = true;
+    }
+    blocked
+}
+
+impl DepthLimitedSearch for SurvivalLookahead {
+    fn name(&self) -> &'static str {
+        "deepening"
+    }
+
+    fn search(&mut self, view: &BoardView, depth: usize, deadline: &Deadline) -> Option<(Action, f64)> {
+        self.dfs(view, &mut view.me.clone(), &mut view.foods.clone(), 0, depth, deadline)
+    }
+
+    fn plan_against(&mut self, known: &[Option<Action>]) {
+        self.known = known.to_vec();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maxn::{MultiplayerSearch, SearchMode};
+
+    // 测试1：时间预算为 0 时直接返回保底的贪心答案
+    #[test]
+    fn test_zero_budget_returns_fallback() {
+        let view = BoardView::parse(5, &[1, 4, 1, 3, 1, 2, 1, 1], 1, &[5, 2, 5, 3, 5, 4, 5, 5], &[1, 5], 50);
+        let config = DeepeningConfig { soft_budget_ms: 0.0, hard_budget_ms: 0.0, max_depth: 8 };
+        let mut strategy = IterativeDeepening::new(config, SurvivalLookahead::default());
+        let expected = GreedyStrategy::default().choose(&view);
+        assert_eq!(strategy.choose(&view), expected);
+        assert_eq!(strategy.completed_depth(), 0);
+    }
+
+    // 测试2：加深后能看出一步之外的死路
+    #[test]
+    fn test_deepening_avoids_dead_end() {
+        // 向上吃到 (1,5) 的果子后被敌蛇、墙和自己的身体围住，下一步必死；向右是开阔区域
+        let view = BoardView::parse(5, &[1, 4, 1, 3, 1, 2, 1, 1], 1, &[2, 5, 3, 5, 3, 4, 3, 3], &[1, 5], 50);
+        let config = DeepeningConfig { soft_budget_ms: 1000.0, hard_budget_ms: 1000.0, max_depth: 4 };
+        let mut strategy = IterativeDeepening::new(config, SurvivalLookahead::default());
+        assert_eq!(strategy.choose(&view), Action::Right);
+        assert_eq!(strategy.completed_depth(), 4);
+    }
+
+    // 测试3：敌蛇在同一局面下反复走同样的方向后，记忆中的走法交给搜索，paranoid 不再假设它们会相撞
+    #[test]
+    fn test_memoized_replies_change_search() {
+        // 与 maxn 测试中的局面相同：敌蛇每次都离开我方蛇头，我方向左
+        let others = [4, 4, 5, 4, 6, 4, 7, 4, 1, 5, 1, 6, 1, 7, 1, 8, 4, 2, 5, 2, 6, 2, 7, 2];
+        let moved = [4, 5, 4, 4, 5, 4, 6, 4, 2, 5, 1, 5, 1, 6, 1, 7, 4, 1, 4, 2, 5, 2, 6, 2];
+        let config = DeepeningConfig { soft_budget_ms: 1000.0, hard_budget_ms: 1000.0, max_depth: 2 };
+        let mut strategy = IterativeDeepening::new(config, MultiplayerSearch::new(SearchMode::Paranoid));
+        let mut choices = Vec::new();
+        for round in 0..4 {
+            let view = BoardView::parse(8, &[3, 3, 3, 2, 3, 1, 4, 1], 3, &others, &[8, 8], 100 - 2 * round);
+            choices.push((strategy.choose(&view), strategy.fallback.known_replies().to_vec()));
+            let view = BoardView::parse(8, &[2, 3, 3, 3, 3, 2, 3, 1], 3, &moved, &[8, 8], 99 - 2 * round);
+            strategy.choose(&view);
+        }
+        assert_eq!(choices[0], (Action::Left, vec![None; 3]));
+        assert_eq!(choices[3], (Action::Right, vec![Some(Action::Up), Some(Action::Right), Some(Action::Down)]));
+    }
+}
@@ -0,0 +1,72 @@
+//! 广度优先搜索距离场：考虑蛇身挡路与蛇尾腾出时间的真实路径距离，
+//! 每回合为每个蛇头构建一次，供果子、争夺与进攻评分共用。
+
+use board_engine::Pos;
+
+use crate::occupancy::Occupancy;
+
+#[derive(Clone, Debug)]
+pub struct DistanceField {
+    n: i32,
+    // 起点所在的回合
+    turn: u32,
+    // 从起点出发的步数，None 表示无法到达
+    dist: Vec<Option<i32>>,
+}
+
+impl DistanceField {
+    /// 第 turn 回合位于 start 的蛇头到各格子的步数
+    pub fn new(occupancy: &Occupancy, start: Pos, turn: u32) -> DistanceField {
+        let dist = occupancy
+            .arrival_turns(start, turn)
+            .into_iter()
+            .map(|arrival| arrival.map(|t| (t - turn) as i32))
+            .collect();
+        DistanceField { n: occupancy.n(), turn, dist }
+    }
+
+    /// 到 pos 的步数，出界或无法到达时为 None
+    pub fn get(&self, (x, y): Pos) -> Option<i32> {
+        if x < 1 || y < 1 || x > self.n || y > self.n {
+            return None;
+        }
+        self.dist[((y - 1) * self.n + (x - 1)) as usize]
+    }
+
+    /// 到达 pos 的回合（起点回合加上步数），用于比较不同回合出发的蛇谁先到
+    pub fn arrival(&self, pos: Pos) -> Option<i32> {
+        self.get(pos).map(|d| d + self.turn as i32)
+    }
+
+    /// 到 targets 中最近一个的步数
+    pub fn nearest(&self, targets: &[Pos]) -> Option<i32> {
+        targets.iter().filter_map(|&t| self.get(t)).min()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 测试1：蛇身挡路时距离大于曼哈顿距离，被围住的格子无法到达
+    #[test]
+    fn test_detour_and_unreachable() {
+        // 5x5 棋盘，一条横在 y=2 的长蛇只在 x=5 留出通道
+        let wall = vec![(4, 2), (3, 2), (2, 2), (1, 2), (1, 1), (2, 1), (3, 1), (4, 1), (5, 1)];
+        let occupancy = Occupancy::from_bodies(5, [&wall]);
+        let field = DistanceField::new(&occupancy, (1, 3), 0);
+        assert_eq!(field.get((1, 3)), Some(0));
+        assert_eq!(field.get((5, 2)), Some(5));
+        // (5,1) 是蛇尾，第 1 回合腾出，但要第 6 回合才能走到
+        assert_eq!(field.get((5, 1)), Some(6));
+        assert_eq!(field.get((0, 3)), None);
+        assert_eq!(field.nearest(&[(5, 5), (2, 3)]), Some(1));
+
+        // 3x3 棋盘，四周的蛇身都来不及腾出，从 (1,1) 哪里也去不了
+        let enemy = vec![(1, 2), (2, 2), (2, 1), (3, 1)];
+        let occupancy = Occupancy::from_bodies(3, [&enemy]);
+        let field = DistanceField::new(&occupancy, (1, 1), 0);
+        assert_eq!(field.get((3, 3)), None);
+        assert_eq!(field.nearest(&[(3, 3), (3, 2)]), None);
+    }
+}
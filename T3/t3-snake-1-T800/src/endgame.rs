@@ -0,0 +1,152 @@
+//! 残局求解：剩余回合不多时，枚举我方在剩余回合内的全部走法，
+//! 在活到游戏结束的前提下最大化吃到的果子数。
+//! 敌蛇蛇身按腾出时间让出格子（不展开敌蛇之后的走法），只计入我方比所有敌蛇严格先到的果子；
+//! 被吃掉的果子会在哪里补充无法预知，不计入。
+
+use board_engine::{Action, Pos};
+
+use crate::distance::DistanceField;
+use crate::occupancy::Occupancy;
+use crate::strategy::{step, BoardView};
+
+/// 剩余回合数（包括本回合）不超过该值时启用求解器，3^8 条路线以内
+pub const ENDGAME_ROUNDS: i32 = 8;
+
+/// 一条路线的结果，按字段顺序比较：先要活到游戏结束，其次果子越多越好，最后活得越久越好
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+struct Outcome {
+    survives: bool,
+    foods: u32,
+    turns: u32,
+}
+
+struct Solver<'a> {
+    obstacles: Occupancy,
+    foods: &'a [Pos],
+    // 最快的敌蛇到达每个果子的回合
+    enemy_arrival: Vec<Option<i32>>,
+    eaten: Vec<bool>,
+    remaining: u32,
+}
+
+impl Solver<'_> {
+    /// 第 turn 回合蛇体为 body 时，剩余回合内的最佳结果
+    fn search(&mut self, body: &[Pos], turn: u32) -> Outcome {
+        if turn == self.remaining {
+            return Outcome { survives: true, ..Outcome::default() };
+        }
+        let mut best = Outcome::default();
+        for action in Action::ALL {
+            if let Some(outcome) = self.try_move(body, turn, action) {
+                best = best.max(outcome);
+            }
+        }
+        best
+    }
+
+    /// 第 turn 回合走 action 之后的最佳结果，不能走时返回 None
+    fn try_move(&mut self, body: &[Pos], turn: u32, action: Action) -> Option<Outcome> {
+        let next = step(body[0], action);
+        if !self.obstacles.can_enter(next, turn + 1) || body[..body.len() - 1].contains(&next) {
+            return None;
+        }
+        let mut moved = Vec::with_capacity(body.len());
+        moved.push(next);
+        moved.extend_from_slice(&body[..body.len() - 1]);
+        let food = (0..self.foods.len()).find(|&i| {
+            self.foods[i] == next
+                && !self.eaten[i]
+                && self.enemy_arrival[i].is_none_or(|enemy| enemy > (turn + 1) as i32)
+        });
+        if let Some(i) = food {
+            self.eaten[i] = true;
+        }
+        let rest = self.search(&moved, turn + 1);
+        if let Some(i) = food {
+            self.eaten[i] = false;
+        }
+        Some(Outcome { survives: rest.survives, foods: rest.foods + food.is_some() as u32, turns: rest.turns + 1 })
+    }
+}
+
+/// 剩余回合不超过 ENDGAME_ROUNDS 时返回求解出的方向；
+/// head_on_risk 为每个格子下一回合被敌蛇蛇头进入的概率，第一步吃到的果子按风险折算，并在结果相同时优先低风险
+pub fn solve_endgame(view: &BoardView, head_on_risk: &[f32]) -> Option<Action> {
+    if view.round < 1 || view.round > ENDGAME_ROUNDS || view.me.is_empty() {
+        return None;
+    }
+    let all = Occupancy::from_bodies(view.n, std::iter::once(&view.me).chain(view.alive_others()));
+    let enemy_fields: Vec<DistanceField> =
+        view.alive_others().map(|body| DistanceField::new(&all, body[0], 0)).collect();
+    let enemy_arrival = view
+        .foods
+        .iter()
+        .map(|&food| enemy_fields.iter().filter_map(|field| field.arrival(food)).min())
+        .collect();
+    let mut solver = Solver {
+        obstacles: Occupancy::from_bodies(view.n, view.alive_others()),
+        foods: &view.foods,
+        enemy_arrival,
+        eaten: vec![false; view.foods.len()],
+        remaining: view.round as u32,
+    };
+
+    let mut best: Option<(Action, Outcome, f32)> = None;
+    for action in Action::ALL {
+        let Some(outcome) = solver.try_move(&view.me, 0, action) else {
+            continue;
+        };
+        let risk = head_on_risk[view.index(step(view.me[0], action))];
+        // 第一步相撞的概率越大，整条路线能兑现的果子越少
+        let expected = (1.0 - risk) * outcome.foods as f32;
+        let better = best.is_none_or(|(_, b, b_risk)| {
+            let b_expected = (1.0 - b_risk) * b.foods as f32;
+            (outcome.survives, expected, outcome.turns, -risk) > (b.survives, b_expected, b.turns, -b_risk)
+        });
+        if better {
+            best = Some((action, outcome, risk));
+        }
+    }
+    best.map(|(action, _, _)| action)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn no_risk(view: &BoardView) -> Vec<f32> {
+        vec![0.0; (view.n * view.n) as usize]
+    }
+
+    // 测试1：剩余回合较多时不启用
+    #[test]
+    fn test_only_near_the_end() {
+        let view = BoardView::parse(5, &[3, 3, 3, 2, 3, 1, 2, 1], 1, &[5, 5, 5, 4, 5, 3, 5, 2], &[1, 5], 9);
+        assert_eq!(solve_endgame(&view, &no_risk(&view)), None);
+    }
+
+    // 测试2：只剩两回合时，去吃两步内的两个果子，而不是一步就能吃到但之后吃不到的果子
+    #[test]
+    fn test_maximizes_food_before_the_end() {
+        // 我方蛇头 (3,3)；左边 (2,3) 一个果子，上方 (3,4)、(3,5) 两个果子
+        let view = BoardView::parse(
+            5,
+            &[3, 3, 3, 2, 3, 1, 2, 1],
+            1,
+            &[5, 1, 5, 2, 5, 3, 5, 4],
+            &[2, 3, 3, 4, 3, 5],
+            2,
+        );
+        assert_eq!(solve_endgame(&view, &no_risk(&view)), Some(Action::Up));
+    }
+
+    // 测试3：最后一回合只看眼前：吃到果子且没有相撞风险
+    #[test]
+    fn test_last_round() {
+        let view = BoardView::parse(5, &[3, 3, 3, 2, 3, 1, 2, 1], 1, &[5, 5, 5, 4, 5, 3, 5, 2], &[4, 3, 2, 3], 1);
+        let mut risk = no_risk(&view);
+        // (4,3) 有敌蛇蛇头进入的风险
+        risk[view.index((4, 3))] = 0.5;
+        assert_eq!(solve_endgame(&view, &risk), Some(Action::Left));
+    }
+}
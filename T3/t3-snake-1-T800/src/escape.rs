@@ -0,0 +1,84 @@
+//! 时间展开的逃生分析：在 (格子, 回合) 状态上搜索，我方蛇体按移动轨迹精确推进，
+//! 敌蛇蛇体按 Occupancy 的腾出时间逐回合让出格子（不考虑敌蛇之后的走法）。
+//! 结果是不考虑敌蛇干扰时我方可以确保存活的回合数。
+
+use std::collections::HashMap;
+
+use board_engine::{Action, Pos};
+
+use crate::occupancy::Occupancy;
+use crate::strategy::step;
+
+/// 逃生搜索，memo 记录 (蛇体, 回合) 状态已知的最长存活回合数
+pub struct EscapeAnalysis<'a> {
+    obstacles: &'a Occupancy,
+    horizon: u32,
+    memo: HashMap<(Vec<Pos>, u32), u32>,
+}
+
+impl<'a> EscapeAnalysis<'a> {
+    /// obstacles 只包含敌蛇（我方蛇体由搜索自己推进），horizon 为需要证明的回合数
+    pub fn new(obstacles: &'a Occupancy, horizon: u32) -> EscapeAnalysis<'a> {
+        EscapeAnalysis { obstacles, horizon, memo: HashMap::new() }
+    }
+
+    /// 第 turn 回合蛇体为 body 时，最多还能存活多少回合（不超过 horizon - turn）
+    pub fn survival_turns(&mut self, body: &[Pos], turn: u32) -> u32 {
+        if turn >= self.horizon {
+            return 0;
+        }
+        if let Some(&turns) = self.memo.get(&(body.to_vec(), turn)) {
+            return turns;
+        }
+        let remaining = self.horizon - turn;
+        let mut best = 0;
+        for action in Action::ALL {
+            let next = step(body[0], action);
+            // 移动后蛇尾腾出，蛇头不能落在其余蛇身上
+            if !self.obstacles.can_enter(next, turn + 1) || body[..body.len() - 1].contains(&next) {
+                continue;
+            }
+            let mut moved = Vec::with_capacity(body.len());
+            moved.push(next);
+            moved.extend_from_slice(&body[..body.len() - 1]);
+            best = best.max(1 + self.survival_turns(&moved, turn + 1));
+            if best == remaining {
+                break;
+            }
+        }
+        self.memo.insert((body.to_vec(), turn), best);
+        best
+    }
+}
+
+/// 从第 turn 回合的 body 出发，在 horizon 回合内可以确保存活的回合数
+pub fn escape_turns(obstacles: &Occupancy, body: &[Pos], turn: u32, horizon: u32) -> u32 {
+    EscapeAnalysis::new(obstacles, horizon).survival_turns(body, turn)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 测试1：空棋盘上 2x2 的空间就足以绕圈无限存活
+    #[test]
+    fn test_loop_in_small_space() {
+        let obstacles = Occupancy::new(2);
+        let body = vec![(1, 2), (1, 1), (2, 1), (2, 2)];
+        assert_eq!(escape_turns(&obstacles, &body, 0, 20), 20);
+    }
+
+    // 测试2：静态地图上走不到蛇尾，但敌蛇蛇体随时间腾出后可以逃生
+    #[test]
+    fn test_escape_through_vacating_enemy() {
+        // 3x3 棋盘，我方在左列向上，敌蛇占住中间一列
+        let me = vec![(1, 3), (1, 2), (1, 1)];
+        let enemy = vec![(2, 1), (2, 2), (2, 3)];
+        let obstacles = Occupancy::from_bodies(3, [&enemy]);
+        assert_eq!(escape_turns(&obstacles, &me, 0, 12), 12);
+        // 敌蛇更长、(2,3) 腾出得太晚时，我方无路可走
+        let enemy = vec![(2, 1), (2, 2), (2, 3), (3, 3), (3, 2), (3, 1)];
+        let obstacles = Occupancy::from_bodies(3, [&enemy]);
+        assert_eq!(escape_turns(&obstacles, &me, 0, 12), 0);
+    }
+}
@@ -0,0 +1,156 @@
+//! 果子争夺裁决：按真实路径比较我方与每条敌蛇到达每个果子的回合。
+//! 严格先到为赢，后到为输，同时到达意味着蛇头相撞同归于尽，记为平局。
+
+use board_engine::Pos;
+
+use crate::distance::DistanceField;
+use crate::occupancy::Occupancy;
+
+/// 争夺结果
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Race {
+    Won,
+    Lost,
+    Tied,
+}
+
+/// 单个果子的裁决
+#[derive(Clone, Debug)]
+pub struct FoodClaim {
+    pub food: Pos,
+    /// 我方到达的回合，无法到达为 None
+    pub mine: Option<i32>,
+    /// 最快的敌蛇到达的回合，没有敌蛇能到达为 None
+    pub enemy: Option<i32>,
+    pub race: Race,
+}
+
+fn judge(mine: Option<i32>, enemy: Option<i32>) -> Race {
+    match (mine, enemy) {
+        (None, _) => Race::Lost,
+        (Some(_), None) => Race::Won,
+        (Some(m), Some(e)) if m < e => Race::Won,
+        (Some(m), Some(e)) if m == e => Race::Tied,
+        _ => Race::Lost,
+    }
+}
+
+/// 对每个果子裁决；my_field 与 enemy_fields 的起点回合可以不同，按到达回合比较
+pub fn arbitrate(my_field: &DistanceField, enemy_fields: &[&DistanceField], foods: &[Pos]) -> Vec<FoodClaim> {
+    foods
+        .iter()
+        .map(|&food| {
+            let mine = my_field.arrival(food);
+            let enemy = enemy_fields.iter().filter_map(|field| field.arrival(food)).min();
+            FoodClaim { food, mine, enemy, race: judge(mine, enemy) }
+        })
+        .collect()
+}
+
+// 路线每一步最多考虑的果子数
+const ROUTE_BRANCH: usize = 4;
+
+/// 依次去吃的果子路线，每一项为 (果子, 我方到达回合)
+pub type Route = Vec<(Pos, i32)>;
+
+/// 从 my_field 的起点出发，最多依次吃 max_len 个果子的最佳路线：
+/// 路线上每个果子都必须比所有敌蛇严格先到，且不晚于游戏结束的回合 last_turn，
+/// 果子越多越好，其次越早吃完越好。
+/// 吃到一个果子后从该果子出发重新计算距离场，敌蛇的到达回合仍按 claims 中的估计。
+pub fn plan_route(
+    occupancy: &Occupancy,
+    my_field: &DistanceField,
+    claims: &[FoodClaim],
+    max_len: usize,
+    last_turn: i32,
+) -> Route {
+    let mut best = Route::new();
+    let mut route = Route::new();
+    let mut used = vec![false; claims.len()];
+    extend_route(occupancy, my_field, claims, max_len, last_turn, &mut used, &mut route, &mut best);
+    best
+}
+
+fn better(a: &Route, b: &Route) -> bool {
+    a.len() > b.len() || (a.len() == b.len() && a.last().map(|&(_, t)| t) < b.last().map(|&(_, t)| t))
+}
+
+#[allow(clippy::too_many_arguments)]
+fn extend_route(
+    occupancy: &Occupancy,
+    field: &DistanceField,
+    claims: &[FoodClaim],
+    max_len: usize,
+    last_turn: i32,
+    used: &mut [bool],
+    route: &mut Route,
+    best: &mut Route,
+) {
+    if better(route, best) {
+        *best = route.clone();
+    }
+    if route.len() == max_len {
+        return;
+    }
+    // 每一层只展开最早能赢的 ROUTE_BRANCH 个果子，果子很多的大棋盘上也能控制耗时
+    let mut candidates: Vec<(usize, i32)> = claims
+        .iter()
+        .enumerate()
+        .filter(|&(i, _)| !used[i])
+        .filter_map(|(i, claim)| field.arrival(claim.food).map(|arrival| (i, arrival)))
+        .filter(|&(i, arrival)| arrival <= last_turn && judge(Some(arrival), claims[i].enemy) == Race::Won)
+        .collect();
+    candidates.sort_by_key(|&(_, arrival)| arrival);
+    candidates.truncate(ROUTE_BRANCH);
+    for (i, arrival) in candidates {
+        let claim = &claims[i];
+        used[i] = true;
+        route.push((claim.food, arrival));
+        if route.len() < max_len {
+            let next = DistanceField::new(occupancy, claim.food, arrival as u32);
+            extend_route(occupancy, &next, claims, max_len, last_turn, used, route, best);
+        } else if better(route, best) {
+            *best = route.clone();
+        }
+        route.pop();
+        used[i] = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 测试1：先到为赢，同时到达为平局，无法到达为输
+    #[test]
+    fn test_arbitrate() {
+        let occupancy = Occupancy::new(5);
+        let mine = DistanceField::new(&occupancy, (1, 1), 0);
+        let enemy = DistanceField::new(&occupancy, (5, 5), 0);
+        let claims = arbitrate(&mine, &[&enemy], &[(1, 3), (3, 3), (5, 4)]);
+        let races: Vec<Race> = claims.iter().map(|c| c.race).collect();
+        assert_eq!(races, vec![Race::Won, Race::Tied, Race::Lost]);
+        assert_eq!(claims[0].mine, Some(2));
+        // 我方晚一回合出发时，原本的平局变成输
+        let late = DistanceField::new(&occupancy, (1, 1), 1);
+        assert_eq!(arbitrate(&late, &[&enemy], &[(3, 3)])[0].race, Race::Lost);
+    }
+
+    // 测试2：路线只包含能赢的果子，优先吃更多的果子
+    #[test]
+    fn test_plan_route() {
+        let occupancy = Occupancy::new(8);
+        let mine = DistanceField::new(&occupancy, (1, 1), 0);
+        let enemy = DistanceField::new(&occupancy, (8, 8), 0);
+        let foods = [(2, 1), (1, 4), (7, 8), (4, 1)];
+        let claims = arbitrate(&mine, &[&enemy], &foods);
+        let route = plan_route(&occupancy, &mine, &claims, 3, 50);
+        // (7,8) 离敌蛇更近，不在路线上；先吃 (2,1) 再吃 (4,1) 和 (1,4) 中按总时间更早的顺序
+        assert_eq!(route.len(), 3);
+        assert_eq!(route[0], ((2, 1), 1));
+        assert!(route.iter().all(|&(food, _)| food != (7, 8)));
+        assert_eq!(plan_route(&occupancy, &mine, &claims, 1, 50), vec![((2, 1), 1)]);
+        // 游戏在第 5 回合结束时来不及吃第三个果子
+        assert_eq!(plan_route(&occupancy, &mine, &claims, 3, 5).len(), 2);
+    }
+}
@@ -0,0 +1,698 @@
+//! 原有的加权启发式策略：果子得分、生存得分与进攻得分加权求和。
+
+use board_engine::{Action, Pos};
+use web_sys::console;
+
+use crate::chokepoint::Chokepoints;
+use crate::config::StrategyConfig;
+use crate::danger::{head_on_risk, EnemyMoveModel};
+use crate::distance::DistanceField;
+use crate::endgame::solve_endgame;
+use crate::escape::escape_turns;
+use crate::foodrace::{arbitrate, plan_route, FoodClaim, Race, Route};
+use crate::occupancy::Occupancy;
+use crate::opponent::{OpponentModels, Prediction};
+use crate::profile::Profile;
+use crate::scoreboard::Scoreboard;
+use crate::standing::{Stance, Standing};
+use crate::strategy::{step, BoardView, SnakeStrategy};
+use crate::territory::voronoi;
+use crate::tracker::{EnemyId, EnemyTracker};
+use crate::LOG_ENABLED;
+
+// 辅助函数：将 (x,y) 转换为数组索引（棋盘坐标从 1 开始）
+fn pos_to_index(x: i32, y: i32, board_size: usize) -> usize {
+    ((y - 1) as usize) * board_size + ((x - 1) as usize)
+}
+
+/// 模拟移动，生成新的蛇体。蛇不会增长，吃到果子时也删除尾部，与引擎的 process_turn 一致
+fn simulate_move(my_snake_coords: &[(i32, i32)], new_head: (i32, i32)) -> Vec<(i32, i32)> {
+    let mut new_body = Vec::with_capacity(my_snake_coords.len());
+    new_body.push(new_head);
+    new_body.extend_from_slice(&my_snake_coords[..my_snake_coords.len() - 1]);
+    new_body
+}
+
+/// 构建危险地图，标记所有蛇（包括我方和其他蛇）所在的格子为危险
+fn build_danger_map(my_snake_coords: &[(i32, i32)], other_snakes_coords: &[Vec<(i32, i32)>], n: i32, board_size: usize) -> Vec<bool> {
+    let mut dangerous = vec![false; board_size * board_size];
+    for snake_body in other_snakes_coords {
+        for &(bx, by) in snake_body {
+            if bx >= 1 && by >= 1 && bx <= n && by <= n {
+                let idx = pos_to_index(bx, by, board_size);
+                dangerous[idx] = true;
+            }
+        }
+    }
+    for &(bx, by) in my_snake_coords {
+        if bx >= 1 && by >= 1 && bx <= n && by <= n {
+            let idx = pos_to_index(bx, by, board_size);
+            dangerous[idx] = true;
+        }
+    }
+    dangerous
+}
+
+/// 使用洪水填充计算从 start 出发的可活动区域面积
+fn compute_free_space(start: (i32, i32), obstacles: &[bool], n: i32, board_size: usize) -> i32 {
+    let mut visited = vec![false; board_size * board_size];
+    let mut queue = Vec::new();
+    let mut area = 0;
+    if start.0 < 1 || start.0 > n || start.1 < 1 || start.1 > n {
+        return 0;
+    }
+    let idx = pos_to_index(start.0, start.1, board_size);
+    if obstacles[idx] {
+        return 0;
+    }
+    queue.push(start);
+    visited[idx] = true;
+    while let Some((cx, cy)) = queue.pop() {
+        area += 1;
+        let neighbors = [(cx, cy+1), (cx-1, cy), (cx, cy-1), (cx+1, cy)];
+        for &(nx, ny) in neighbors.iter() {
+            if nx < 1 || nx > n || ny < 1 || ny > n {
+                continue;
+            }
+            let ni = pos_to_index(nx, ny, board_size);
+            if !visited[ni] && !obstacles[ni] {
+                visited[ni] = true;
+                queue.push((nx, ny));
+            }
+        }
+    }
+    area
+}
+
+// 逃生分析最多需要证明的存活回合数，剩余回合更少时只需活到游戏结束
+pub(crate) const ESCAPE_HORIZON: u32 = 12;
+
+/// 剩余 remaining 回合（包括本回合）时，本回合之后还需要存活的回合数，不超过 ESCAPE_HORIZON
+pub(crate) fn survival_horizon(remaining: i32) -> u32 {
+    (remaining.max(1) as u32 - 1).min(ESCAPE_HORIZON)
+}
+
+/// 重写后的生存得分函数：
+/// 使用考虑蛇尾腾出时间的洪水填充计算新头的可活动区域；
+/// 同时做时间展开的逃生分析，不考虑敌蛇干扰也无法确保存活 horizon 回合时
+/// 返回极低得分，避免走入死路。游戏临近结束时 horizon 变小，空间得分按比例缩小。
+/// other_snakes_coords 为移动前的敌蛇，new_body 为移动一步后的我方蛇体。
+pub(crate) fn compute_survival_score(
+    new_head: (i32, i32),
+    new_body: &[(i32, i32)],
+    other_snakes_coords: &[Vec<(i32, i32)>],
+    n: i32,
+    my_length: usize,
+    horizon: u32
+) -> f32 {
+    // 构建随时间变化的占用：敌蛇从第 0 回合的位置开始腾出，我方新身体从第 1 回合开始腾出
+    let mut occupancy = Occupancy::new(n);
+    for snake_body in other_snakes_coords {
+        occupancy.add_snake(snake_body, 0);
+    }
+    // 逃生分析只需要敌蛇，我方蛇体由搜索按轨迹推进
+    let escape = escape_turns(&occupancy, new_body, 1, horizon + 1);
+    occupancy.add_snake(new_body, 1);
+
+    // 计算从 new_head 出发的自由区域面积
+    let arrival = occupancy.arrival_turns(new_head, 1);
+    let space = arrival.iter().flatten().count() as i32;
+
+    // 无法确保存活到 horizon 时视为死路，撑得越久扣分越少
+    if escape < horizon {
+        return -1000.0 + 50.0 * escape as f32;
+    }
+
+    // 根据自由空间面积计算基础生存得分，按剩余回合缩放
+    let scale = horizon as f32 / ESCAPE_HORIZON as f32;
+    if space < my_length as i32 {
+        -100.0 * scale
+    } else {
+        50.0 * (space as f32).sqrt() * scale
+    }
+}
+
+/// 领地得分：我方移动到 new_head 后（第 1 回合），与第 0 回合的敌蛇蛇头同时扩展的 Voronoi 领地，
+/// 按领地格子数加上领地内的果子（每个折算 food_value 个格子）计分；occupancy 已包含我方移动后的蛇体
+fn compute_territory_score(
+    occupancy: &Occupancy,
+    enemy_sources: &[Option<(Pos, u32)>],
+    new_head: Pos,
+    food_coords: &[Pos],
+    food_value: f32
+) -> f32 {
+    let mut sources = vec![Some((new_head, 1))];
+    sources.extend_from_slice(enemy_sources);
+    let territory = voronoi(occupancy, &sources, food_coords);
+    territory.cells[0] as f32 + food_value * territory.foods[0] as f32
+}
+
+/// 口袋得分：new_head 位于只有一个入口的口袋中，且某条敌蛇严格先于我方到达入口时，
+/// 敌蛇可以把我方封在口袋里，按口袋的格子数扣分；同时到达是入口处的蛇头相撞，已由相撞风险计分。
+/// my_field 为我方移动后从 new_head 出发的距离场
+fn compute_pocket_score(
+    chokepoints: &Chokepoints,
+    my_field: &DistanceField,
+    head_fields: &[&DistanceField],
+    new_head: Pos,
+    config: &StrategyConfig
+) -> f32 {
+    let Some(pocket) = chokepoints.pocket(new_head) else {
+        return 0.0;
+    };
+    let mine = my_field.arrival(pocket.entrance);
+    let sealed = head_fields.iter().filter_map(|field| field.arrival(pocket.entrance)).any(|enemy| mine.is_none_or(|mine| enemy < mine));
+    if !sealed {
+        return 0.0;
+    }
+    -config.pocket_cost * (1.0 - pocket.size as f32 / config.pocket_size.max(1.0)).max(0.0)
+}
+
+/// 加权启发式策略，保存需要跨回合记忆的敌蛇记录与得分
+pub struct GreedyStrategy {
+    // 按果子的消失精确记录我方与每条敌蛇（包括已死亡的）的得分
+    pub(crate) scoreboard: Scoreboard,
+    // 按移动规则跟踪敌蛇，提供整局不变的编号与死亡记录
+    pub(crate) tracker: EnemyTracker,
+    // 本回合使用的参数档案，每回合按棋盘边长、存活的蛇数与果子数重新推导
+    pub(crate) profile: Option<Profile>,
+    // 估计敌蛇走向的行为模型，None 表示在合法方向中均匀选择；不随新局清空
+    enemy_model: Option<Box<dyn EnemyMoveModel>>,
+    // 可调参数，不随新局清空
+    pub(crate) config: StrategyConfig,
+    // 在线学习的敌蛇行为模型，None 表示不学习（从敌蛇视角运行的我方启发式不再递归建模）
+    pub(crate) opponents: Option<OpponentModels>,
+    // 本回合记忆中已知的敌蛇走法，与 view.others 下标一致，供搜索固定敌蛇的应对
+    known_replies: Vec<Option<Action>>,
+}
+
+impl Default for GreedyStrategy {
+    fn default() -> Self {
+        GreedyStrategy {
+            scoreboard: Scoreboard::default(),
+            tracker: EnemyTracker::default(),
+            profile: None,
+            enemy_model: None,
+            config: StrategyConfig::default(),
+            opponents: Some(OpponentModels::default()),
+            known_replies: Vec::new(),
+        }
+    }
+}
+
+impl GreedyStrategy {
+    /// 使用指定的敌蛇行为模型估计蛇头相撞风险
+    pub fn with_enemy_model(model: Box<dyn EnemyMoveModel>) -> GreedyStrategy {
+        GreedyStrategy { enemy_model: Some(model), ..GreedyStrategy::default() }
+    }
+
+    /// 使用指定的可调参数
+    pub fn with_config(config: StrategyConfig) -> GreedyStrategy {
+        GreedyStrategy { config, ..GreedyStrategy::default() }
+    }
+
+    /// 不学习敌蛇行为的加权启发式，用作对手模型中的 mirror 模型
+    pub(crate) fn mirror() -> GreedyStrategy {
+        GreedyStrategy { opponents: None, ..GreedyStrategy::default() }
+    }
+
+    /// 上一次 choose 时记忆中已知的敌蛇走法，与 view.others 下标一致；不学习敌蛇行为时全为 None
+    pub fn known_replies(&self) -> &[Option<Action>] {
+        &self.known_replies
+    }
+
+    /// 计算果子得分：若吃到果子则加 eat_bonus；否则朝路线上第一个严格先到的果子前进，按到达回合扣分，
+    /// 路线上每个能赢的果子加 route_food_value；同时到达的果子意味着蛇头相撞，按到达回合乘以 tied_penalty 扣分；
+    /// 我方无法到达的果子按 unreachable_food_score 计分
+    fn compute_food_score(&self, claims: &[FoodClaim], route: &Route, center: (f64, f64), tied_penalty: f32, eat: bool) -> f32 {
+        let config = &self.config;
+        let mut score = 0.0;
+        // 果子靠近中心（曼哈顿距离小于 center_radius）时增加额外权重
+        let center_bonus = |(fx, fy): (i32, i32)| {
+            let center_dist = ((fx as f64) - center.0).abs() + ((fy as f64) - center.1).abs();
+            if center_dist < config.center_radius as f64 { config.center_bonus } else { 0.0 }
+        };
+
+        if eat {
+            score += config.eat_bonus;
+        } else if !claims.is_empty() {
+            for claim in claims {
+                match (claim.mine, claim.race) {
+                    (None, _) => score += config.unreachable_food_score,
+                    (Some(arrival), Race::Tied) => score += -tied_penalty * arrival as f32,
+                    _ => {}
+                }
+            }
+            match route.first() {
+                Some(&(food, arrival)) => {
+                    score += -arrival as f32 + center_bonus(food) + config.route_food_value * route.len() as f32;
+                }
+                // 没有能赢的果子时，仍然靠近最近的可到达果子
+                None => {
+                    if let Some(arrival) = claims.iter().filter_map(|c| c.mine).min() {
+                        score += -arrival as f32;
+                    }
+                }
+            }
+        }
+        score
+    }
+
+    /// 重新设计的进攻得分函数：
+    /// 场景1：对于每个敌蛇，按比分形势判断与它同归于尽后我方名次是否不会变差；
+    /// 如果划算，并且我方新头靠近该敌蛇头，则奖励额外分（同归于尽奖励）；
+    /// 场景2：对于每个敌蛇，如果我方新头靠近（距离不超过 contested_radius），模拟阻断后计算敌蛇自由空间，
+    /// 若自由空间低于阈值，则奖励 (阈值 - 自由空间)/距离 得分。
+    /// 距离为敌蛇蛇头走到我方新头的真实路径距离，走不到时不计。
+    #[allow(clippy::too_many_arguments)]
+    fn compute_aggression_score(
+        &self,
+        new_head: (i32, i32),
+        other_heads: &[(i32, i32, usize)],
+        head_fields: &[&DistanceField],
+        n: i32,
+        board_size: usize,
+        dangerous: &[bool],
+        mapping: &[usize],
+        standing: &Standing,
+        kamikaze_bonus: f32
+    ) -> f32 {
+        let mut aggression_score = 0.0;
+        let free_space_threshold = self.config.free_space_threshold as i32;
+        let radius = self.config.contested_radius as i32;
+        // 场景1：同归于尽机会
+        for (&id, field) in mapping.iter().zip(head_fields) {
+            let enemy_score = self.scoreboard.enemy(id) as f32;
+            if LOG_ENABLED {
+                console::log_1(&format!("[AGGRESSION] My score: {}, Enemy score: {}", standing.my_score, enemy_score).into());
+            }
+            // 旁观者包括已死亡的蛇：我方得分冻结后可能再也追不上它们
+            let bystanders: Vec<f32> = self
+                .scoreboard
+                .enemies()
+                .iter()
+                .enumerate()
+                .filter(|&(other, _)| other != id)
+                .map(|(_, &s)| s as f32)
+                .collect();
+            if standing.trade_pays_off(enemy_score, &bystanders) {
+                let dist = field.get(new_head).unwrap_or(i32::MAX);
+                if dist <= radius && !dangerous[pos_to_index(new_head.0, new_head.1, board_size)] {
+                    // 奖励值随档案变化：敌蛇越多，同归于尽越可能把名次让给旁观者
+                    aggression_score += kamikaze_bonus;
+                }
+            }
+        }
+        // 场景2：逼死敌蛇
+        for (&(hx, hy, _), field) in other_heads.iter().zip(head_fields) {
+            let dist = field.get(new_head).unwrap_or(i32::MAX);
+            if dist > radius {
+                continue;
+            }
+            let mut obstacles = dangerous.to_vec();
+            let our_idx = pos_to_index(new_head.0, new_head.1, board_size);
+            obstacles[our_idx] = true;
+            let enemy_space = compute_free_space((hx, hy), &obstacles, n, board_size);
+            if enemy_space < free_space_threshold {
+                aggression_score += (free_space_threshold - enemy_space) as f32 / dist as f32;
+            }
+        }
+        aggression_score
+    }
+}
+
+impl SnakeStrategy for GreedyStrategy {
+    fn name(&self) -> &'static str {
+        "greedy"
+    }
+
+    fn reset(&mut self) {
+        let enemy_model = self.enemy_model.take();
+        let config = std::mem::take(&mut self.config);
+        let opponents = self.opponents.as_ref().map(|_| OpponentModels::default());
+        *self = GreedyStrategy { enemy_model, config, opponents, ..GreedyStrategy::default() };
+    }
+
+    fn config(&self) -> Option<&StrategyConfig> {
+        Some(&self.config)
+    }
+
+    fn config_mut(&mut self) -> Option<&mut StrategyConfig> {
+        Some(&mut self.config)
+    }
+
+    fn tracker(&self) -> Option<&EnemyTracker> {
+        Some(&self.tracker)
+    }
+
+    fn scoreboard(&self) -> Option<&Scoreboard> {
+        Some(&self.scoreboard)
+    }
+
+    fn opponents(&self) -> Option<&OpponentModels> {
+        self.opponents.as_ref()
+    }
+
+    /// 会话内部跟踪敌蛇，保证敌蛇编号固定；
+    /// 同时按上一回合消失的果子记录我方与每条敌蛇的得分（每吃到一个果子加 1 分）。
+    fn choose(&mut self, view: &BoardView) -> Action {
+        let n = view.n;
+        let board_size = n as usize;
+        let my_snake_coords = &view.me;
+        let my_length = my_snake_coords.len();
+        let other_snakes_coords = &view.others;
+        // 其他蛇的 (蛇头x, 蛇头y, 蛇体长度)
+        let other_heads: Vec<(i32, i32, usize)> = view.alive_others().map(|body| (body[0].0, body[0].1, body.len())).collect();
+        // 每回合重新推导参数档案，蛇死亡后随之切换
+        let profile = Profile::from_view(view, &self.config);
+        self.profile = Some(profile.clone());
+        if LOG_ENABLED {
+            console::log_1(&format!("[OTHER_SNAKES] Parsed heads: {:?}", other_heads).into());
+        }
+        // 跟踪存活的敌蛇，mapping[i] 为第 i 条存活敌蛇的固定编号（一次性调用）
+        let alive_bodies: Vec<Vec<Pos>> = view.alive_others().cloned().collect();
+        let mapping = self.tracker.update(&alive_bodies, view.round).to_vec();
+        let food_coords = view.foods.clone();
+        if LOG_ENABLED {
+            console::log_1(&format!("[FOODS] Parsed: {:?}", food_coords).into());
+        }
+        // 按上一回合消失的果子记分，使用 mapping 保持固定索引
+        let enemy_heads: Vec<(EnemyId, Pos)> = mapping.iter().zip(&alive_bodies).map(|(&id, body)| (id, body[0])).collect();
+        self.scoreboard.update(my_snake_coords[0], &enemy_heads, &food_coords);
+        if LOG_ENABLED {
+            console::log_1(&format!("[SCORES] Mine: {}, enemies: {:?}", self.scoreboard.mine(), self.scoreboard.enemies()).into());
+        }
+        // 检验上一回合对敌蛇走法的预测，再预测本回合每条敌蛇的下一步
+        let prediction = match self.opponents.as_mut() {
+            Some(opponents) => {
+                opponents.observe(&enemy_heads);
+                opponents.predict(view, &mapping)
+            }
+            None => Prediction::default(),
+        };
+        self.known_replies = (0..other_snakes_coords.len()).map(|enemy| prediction.known_move(enemy)).collect();
+        // 比分形势：出现过的敌蛇（包括已死亡的，得分保留）都参与排名
+        let all_scores: Vec<f32> = self.scoreboard.enemies().iter().map(|&s| s as f32).collect();
+        let standing = Standing::new(self.scoreboard.mine() as f32, &all_scores, view.round, profile.food_rate);
+        let stance = standing.stance();
+        if LOG_ENABLED {
+            console::log_1(&format!("[STANDING] {:?} {:?}", standing, stance).into());
+        }
+        // 构建危险地图
+        let dangerous = build_danger_map(my_snake_coords, other_snakes_coords, n, board_size);
+        // 随时间变化的占用：每节蛇身腾出的回合
+        let occupancy = Occupancy::from_bodies(n, std::iter::once(my_snake_coords).chain(view.alive_others()));
+        // 每条敌蛇蛇头的距离场，每回合构建一次，与 view.others 下标一致
+        let enemy_fields: Vec<Option<DistanceField>> = other_snakes_coords
+            .iter()
+            .map(|body| body.first().map(|&head| DistanceField::new(&occupancy, head, 0)))
+            .collect();
+        let head_fields: Vec<&DistanceField> = enemy_fields.iter().flatten().collect();
+        // 争夺果子时只考虑敌蛇可能的走法：从每个可能的下一格出发的距离场（记忆中已知走法的敌蛇只有一个）；
+        // 没有预测时使用蛇头的距离场
+        let move_fields: Vec<Vec<DistanceField>> = (0..other_snakes_coords.len())
+            .map(|enemy| match (self.opponents.is_some(), prediction.plausible_moves(enemy)) {
+                (true, Some(moves)) if !moves.is_empty() => {
+                    let head = other_snakes_coords[enemy][0];
+                    moves.iter().map(|&a| DistanceField::new(&occupancy, step(head, a), 1)).collect()
+                }
+                _ => Vec::new(),
+            })
+            .collect();
+        let contest_fields: Vec<&DistanceField> = enemy_fields
+            .iter()
+            .zip(&move_fields)
+            .flat_map(|(field, moves)| if moves.is_empty() { field.iter().collect::<Vec<_>>() } else { moves.iter().collect() })
+            .collect();
+        // 下一回合空格子图的割点与口袋
+        let chokepoints = Chokepoints::from_view(view);
+        // 敌蛇从第 0 回合开始腾出的占用，Voronoi 领地在此基础上加入我方移动后的蛇体
+        let enemy_occupancy = Occupancy::from_bodies(n, view.alive_others());
+        let enemy_sources: Vec<Option<(Pos, u32)>> = view.alive_others().map(|body| Some((body[0], 0))).collect();
+        // 蛇头相撞风险：每个格子下一回合被敌蛇蛇头进入的概率
+        // 指定了行为模型时优先使用，否则使用在线学习的预测
+        let model: Option<&dyn EnemyMoveModel> = match (self.enemy_model.as_deref(), self.opponents.is_some()) {
+            (Some(model), _) => Some(model),
+            (None, true) => Some(&prediction),
+            (None, false) => None,
+        };
+        let head_on_risk = head_on_risk(view, model);
+        // 最后几个回合精确求解，直接采用
+        if let Some(action) = solve_endgame(view, &head_on_risk) {
+            if LOG_ENABLED {
+                console::log_1(&format!("[ENDGAME] Round {}: {}", view.round, action.code()).into());
+            }
+            return action;
+        }
+        // 剩余回合越少，长期的生存与领地越不重要
+        let horizon = survival_horizon(view.round);
+        let horizon_scale = horizon as f32 / ESCAPE_HORIZON as f32;
+        // 权重设置，随档案变化
+        let config = &self.config;
+        let score_weight = config.score_weight;
+        let survival_weight = profile.survival_weight;
+        let aggression_weight = profile.aggression_weight;
+        // 领先时加大相撞扣分，落后且时间不多时接受相撞风险
+        let head_on_weight = match stance {
+            Stance::Cautious => config.head_on_weight_cautious,
+            Stance::Neutral => config.head_on_weight_neutral,
+            Stance::Reckless => config.head_on_weight_reckless,
+        };
+        let tied_penalty = match stance {
+            Stance::Cautious => profile.tied_penalty_leading,
+            Stance::Neutral => profile.tied_penalty,
+            Stance::Reckless => 0.0,
+        };
+        let territory_weight = profile.territory_weight;
+
+        let mut best_dir = Action::Up;
+        let mut best_score: f32 = -1e9;
+        for action in Action::ALL {
+            let dir_idx = action.code();
+            let head = my_snake_coords[0];
+            if LOG_ENABLED {
+                console::log_1(&format!("[DIRECTION {}] Current head: {:?}", dir_idx, head).into());
+            }
+            let new_head = step(head, action);
+            if new_head.0 < 1 || new_head.0 > n || new_head.1 < 1 || new_head.1 > n {
+                if LOG_ENABLED {
+                    console::log_1(&format!("[DIRECTION {}] Skipped: wall collision at {:?}", dir_idx, new_head).into());
+                }
+                continue;
+            }
+            let new_idx = pos_to_index(new_head.0, new_head.1, board_size);
+            // 所有蛇（包括敌蛇）的蛇尾下一回合都会腾出，可以跟随
+            if !occupancy.can_enter(new_head, 1) {
+                if LOG_ENABLED {
+                    console::log_1(&format!("[DIRECTION {}] Skipped: collision at {:?}", dir_idx, new_head).into());
+                }
+                continue;
+            }
+            let new_body = simulate_move(my_snake_coords, new_head);
+            if LOG_ENABLED {
+                console::log_1(&format!("[DIRECTION {}] Simulated body: {:?}", dir_idx, new_body).into());
+            }
+            let eat = food_coords.contains(&new_head);
+            // 移动后的占用：敌蛇从第 0 回合开始腾出，我方新身体从第 1 回合开始腾出
+            let mut my_occupancy = enemy_occupancy.clone();
+            my_occupancy.add_snake(&new_body, 1);
+            let my_field = DistanceField::new(&my_occupancy, new_head, 1);
+            // 与每条敌蛇比较到达每个果子的回合，规划只包含严格先到的果子的路线
+            let claims = arbitrate(&my_field, &contest_fields, &food_coords);
+            let route = plan_route(&my_occupancy, &my_field, &claims, profile.route_len, view.round);
+            if LOG_ENABLED {
+                console::log_1(&format!("[DIRECTION {}] Food route: {:?}", dir_idx, route).into());
+            }
+            let food_score = self.compute_food_score(&claims, &route, profile.center, tied_penalty, eat);
+            let survival_score = compute_survival_score(new_head, &new_body, other_snakes_coords, n, my_length, horizon);
+            let aggression_score = self.compute_aggression_score(new_head, &other_heads, &head_fields, n, board_size, &dangerous, &mapping, &standing, profile.kamikaze_bonus);
+            let proximity_score = if stance == Stance::Cautious {
+                head_fields
+                    .iter()
+                    .filter_map(|field| field.get(new_head))
+                    .filter(|&dist| dist <= config.contested_radius as i32)
+                    .map(|dist| -config.head_proximity_cost / dist.max(1) as f32)
+                    .sum()
+            } else {
+                0.0
+            };
+            let head_on_score = -config.head_on_cost * head_on_risk[new_idx];
+            let pocket_score = compute_pocket_score(&chokepoints, &my_field, &head_fields, new_head, config);
+            let territory_score =
+                compute_territory_score(&my_occupancy, &enemy_sources, new_head, &food_coords, config.territory_food_value);
+            if LOG_ENABLED {
+                console::log_1(&format!("[DIRECTION {}] Food score: {}", dir_idx, food_score).into());
+                console::log_1(&format!("[DIRECTION {}] Survival score: {}", dir_idx, survival_score).into());
+                console::log_1(&format!("[DIRECTION {}] Aggression score: {}", dir_idx, aggression_score).into());
+                console::log_1(&format!("[DIRECTION {}] Head-on score: {}", dir_idx, head_on_score).into());
+                console::log_1(&format!("[DIRECTION {}] Territory score: {}", dir_idx, territory_score).into());
+                console::log_1(&format!("[DIRECTION {}] Proximity score: {}", dir_idx, proximity_score).into());
+                console::log_1(&format!("[DIRECTION {}] Pocket score: {}", dir_idx, pocket_score).into());
+            }
+            let total_score = food_score * score_weight
+                + survival_score * survival_weight
+                + aggression_score * aggression_weight
+                + head_on_score * head_on_weight
+                + proximity_score
+                + pocket_score
+                + territory_score * territory_weight * horizon_scale;
+            if LOG_ENABLED {
+                console::log_1(&format!("[DIRECTION {}] Total score: {}", dir_idx, total_score).into());
+            }
+            if total_score > best_score {
+                best_score = total_score;
+                best_dir = action;
+            }
+        }
+        if LOG_ENABLED {
+            console::log_1(&format!("[RESULT] Chosen direction: {}", best_dir.code()).into());
+        }
+        best_dir
+    }
+}
+
+/// 使用嵌入的自我对弈调参结果（见 StrategyConfig::tuned）的加权启发式策略
+pub struct TunedStrategy(GreedyStrategy);
+
+impl Default for TunedStrategy {
+    fn default() -> Self {
+        TunedStrategy(GreedyStrategy::with_config(StrategyConfig::tuned()))
+    }
+}
+
+impl SnakeStrategy for TunedStrategy {
+    fn name(&self) -> &'static str {
+        "tuned"
+    }
+
+    fn choose(&mut self, view: &BoardView) -> Action {
+        self.0.choose(view)
+    }
+
+    fn reset(&mut self) {
+        self.0.reset();
+    }
+
+    fn config(&self) -> Option<&StrategyConfig> {
+        self.0.config()
+    }
+
+    fn config_mut(&mut self) -> Option<&mut StrategyConfig> {
+        self.0.config_mut()
+    }
+
+    fn tracker(&self) -> Option<&EnemyTracker> {
+        self.0.tracker()
+    }
+
+    fn scoreboard(&self) -> Option<&Scoreboard> {
+        self.0.scoreboard()
+    }
+
+    fn opponents(&self) -> Option<&OpponentModels> {
+        self.0.opponents()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::profile::ProfileKind;
+
+    // 测试1：参数档案每回合按存活的蛇数推导，吃到上一回合的果子累计得分，reset 后清空
+    #[test]
+    fn test_profile_and_score_reset() {
+        let mut strategy = GreedyStrategy::default();
+        let kind = |strategy: &GreedyStrategy| strategy.profile.as_ref().map(|p| p.kind);
+        let view = BoardView::parse(5, &[1, 4, 1, 3, 1, 2, 1, 1], 1, &[5, 2, 5, 3, 5, 4, 5, 5], &[1, 5], 50);
+        assert_eq!(strategy.choose(&view), Action::Up);
+        assert_eq!(kind(&strategy), Some(ProfileKind::Duel));
+        let view = BoardView::parse(5, &[1, 5, 1, 4, 1, 3, 1, 2], 1, &[5, 2, 5, 3, 5, 4, 5, 5], &[3, 3], 49);
+        strategy.choose(&view);
+        assert_eq!(strategy.scoreboard.mine(), 1);
+        // 敌蛇死亡后切换到单独的档案
+        let view = BoardView::parse(5, &[2, 5, 1, 5, 1, 4, 1, 3], 0, &[], &[3, 3], 48);
+        strategy.choose(&view);
+        assert_eq!(kind(&strategy), Some(ProfileKind::Solo));
+        strategy.reset();
+        assert_eq!(strategy.scoreboard.mine(), 0);
+        assert_eq!(strategy.profile, None);
+    }
+
+    // 测试2：唯一的出路是敌蛇的蛇尾时，跟随蛇尾而不是撞上自己
+    #[test]
+    fn test_follows_enemy_tail() {
+        let mut strategy = GreedyStrategy::default();
+        // 我方蛇头在左下角 (1,1)，上方是自己的身体，右边 (2,1) 是敌蛇蛇尾
+        let view = BoardView::parse(5, &[1, 1, 1, 2, 1, 3, 1, 4], 1, &[3, 3, 3, 2, 2, 2, 2, 1], &[5, 5], 50);
+        assert_eq!(strategy.choose(&view), Action::Right);
+    }
+
+    // 测试3：蛇不会增长，吃到果子后模拟的蛇体长度不变
+    #[test]
+    fn test_simulate_move_keeps_length() {
+        let body = vec![(2, 1), (2, 2), (1, 2), (1, 3)];
+        let moved = simulate_move(&body, (1, 1));
+        assert_eq!(moved, vec![(1, 1), (2, 1), (2, 2), (1, 2)]);
+        assert_eq!(moved.len(), body.len());
+    }
+
+    // 测试4：果子得分按真实到达回合计算，无法到达的果子单独计分，同时到达的果子扣分
+    #[test]
+    fn test_food_score_uses_races() {
+        let strategy = GreedyStrategy::default();
+        // 3x3 棋盘，敌蛇围住左下角，(3,3) 的果子曼哈顿距离为 4 但走不到
+        let enemy = vec![(1, 2), (2, 2), (2, 1), (3, 1)];
+        let occupancy = Occupancy::from_bodies(3, [&enemy]);
+        let field = DistanceField::new(&occupancy, (1, 1), 0);
+        let claims = arbitrate(&field, &[], &[(3, 3)]);
+        let route = plan_route(&occupancy, &field, &claims, 1, 50);
+        assert_eq!(strategy.compute_food_score(&claims, &route, (2.0, 2.0), 0.0, false), strategy.config.unreachable_food_score);
+        // 空棋盘上朝能赢的果子前进
+        let occupancy = Occupancy::new(5);
+        let field = DistanceField::new(&occupancy, (1, 1), 0);
+        let enemy = DistanceField::new(&occupancy, (5, 5), 0);
+        let claims = arbitrate(&field, &[&enemy], &[(1, 3)]);
+        let route = plan_route(&occupancy, &field, &claims, 1, 50);
+        assert_eq!(strategy.compute_food_score(&claims, &route, (3.0, 3.0), 0.0, false), -2.0 + strategy.config.route_food_value);
+        // 同时到达的果子按 tied_penalty 额外扣分
+        let claims = arbitrate(&field, &[&enemy], &[(3, 3)]);
+        assert_eq!(claims[0].race, Race::Tied);
+        let route = plan_route(&occupancy, &field, &claims, 1, 50);
+        assert_eq!(strategy.compute_food_score(&claims, &route, (3.0, 3.0), 0.0, false), -4.0);
+        assert_eq!(strategy.compute_food_score(&claims, &route, (3.0, 3.0), 3.0, false), -3.0 * 4.0 - 4.0);
+    }
+
+    // 测试5：敌蛇严格先到口袋入口时才扣分，同时到达是蛇头相撞，不算封口
+    #[test]
+    fn test_pocket_needs_strictly_earlier_enemy() {
+        let config = StrategyConfig::default();
+        // 5x5 棋盘，(3,2) 下方是只有一个入口的 4 格口袋，我方蛇头进入 (3,3)
+        let rows = [".....", ".....", "##.##", "#...#", "#####"];
+        let free: Vec<bool> = rows.iter().flat_map(|row| row.chars().map(|c| c != '#')).collect();
+        let chokepoints = Chokepoints::new(5, &free);
+        let occupancy = Occupancy::new(5);
+        let my_field = DistanceField::new(&occupancy, (3, 3), 1);
+        assert_eq!(my_field.arrival((3, 2)), Some(2));
+        let tied = DistanceField::new(&occupancy, (2, 1), 0);
+        assert_eq!(compute_pocket_score(&chokepoints, &my_field, &[&tied], (3, 3), &config), 0.0);
+        let earlier = DistanceField::new(&occupancy, (3, 1), 0);
+        assert_eq!(compute_pocket_score(&chokepoints, &my_field, &[&earlier], (3, 3), &config), -config.pocket_cost * 0.5);
+    }
+
+    // 测试6：在自己蛇尾旁边吃果子后可以绕圈存活，逃生分析不能按多一节的蛇体计算
+    #[test]
+    fn test_escape_after_eating_next_to_tail() {
+        // 3x3 棋盘，敌蛇占住右列与 (2,3)，靠近我方的 (2,3) 最后腾出；我方在剩下的 5 格中，(1,1) 有果子
+        let enemy = vec![(2, 3), (3, 3), (3, 2), (3, 1)];
+        let body = vec![(2, 1), (2, 2), (1, 2), (1, 3)];
+        let new_body = simulate_move(&body, (1, 1));
+        // 若蛇体多一节则无路可走
+        let obstacles = Occupancy::from_bodies(3, [&enemy]);
+        let grown = [&[(1, 1)][..], &body].concat();
+        assert_eq!(escape_turns(&obstacles, &grown, 1, ESCAPE_HORIZON + 1), 0);
+        // 新头 (1,1) 紧挨着新蛇尾 (1,2)，可以在 2x2 中绕圈
+        let score = compute_survival_score((1, 1), &new_body, &[enemy], 3, 4, ESCAPE_HORIZON);
+        assert!(score > -100.0, "{}", score);
+    }
+}
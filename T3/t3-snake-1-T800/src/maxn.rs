@@ -0,0 +1,289 @@
+//! 多人博弈树搜索：同时移动被拆成按编号依次决策（我方先决策），所有蛇都决策后统一结算。
+//! Max-n 假设每条蛇最大化自己的份额，使用浅层剪枝；
+//! Paranoid 假设所有敌蛇联合最小化我方的份额，退化为两人零和博弈，使用 alpha-beta 剪枝。
+//! 记忆中已知走法的敌蛇在第一步不再分支，直接按已知的走法应对。
+
+use board_engine::Action;
+
+use crate::clock::Deadline;
+use crate::deepening::DepthLimitedSearch;
+use crate::sim::SimState;
+use crate::strategy::BoardView;
+
+/// 对手模型
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SearchMode {
+    MaxN,
+    Paranoid,
+}
+
+// 局面强度：存活的基础分、每个果子的分值、可活动空间占棋盘的比例
+const ALIVE_VALUE: f64 = 1.0;
+const FOOD_VALUE: f64 = 0.5;
+const AREA_VALUE: f64 = 1.0;
+// 已死亡的蛇保留的比例：死亡前吃到的果子仍计入最终得分
+const DEAD_FOOD_RATIO: f64 = 0.1;
+
+/// 每条蛇在局面中的份额，总和为 1，是 max-n 浅层剪枝成立的前提
+fn shares(state: &SimState) -> Vec<f64> {
+    let cells = (state.n * state.n) as f64;
+    let strength: Vec<f64> = state
+        .bodies
+        .iter()
+        .zip(&state.eaten)
+        .map(|(body, &eaten)| match body {
+            Some(body) => {
+                ALIVE_VALUE + FOOD_VALUE * eaten as f64 + AREA_VALUE * state.area_from(body[0]) as f64 / cells
+            }
+            None => 0.01 + DEAD_FOOD_RATIO * FOOD_VALUE * eaten as f64,
+        })
+        .collect();
+    let total: f64 = strength.iter().sum();
+    strength.iter().map(|s| s / total).collect()
+}
+
+/// 多人搜索，放入 IterativeDeepening 中逐层加深；depth 为完整回合数
+pub struct MultiplayerSearch {
+    mode: SearchMode,
+    // 本层搜索的节点数，便于比较两种剪枝的效果
+    nodes: usize,
+    // 本回合已知的敌蛇走法，与 view.others 下标一致
+    known: Vec<Option<Action>>,
+    // 按模拟中的蛇编号排列的已知走法（0 为我方），每次搜索时由 known 换算
+    fixed: Vec<Option<Action>>,
+}
+
+impl MultiplayerSearch {
+    pub fn new(mode: SearchMode) -> MultiplayerSearch {
+        MultiplayerSearch { mode, nodes: 0, known: Vec::new(), fixed: Vec::new() }
+    }
+
+    pub fn nodes(&self) -> usize {
+        self.nodes
+    }
+
+    /// 当前应决策的蛇：player 之后第一条存活的蛇；全部决策完毕时返回 None
+    fn next_player(state: &SimState, player: usize) -> Option<usize> {
+        (player..state.bodies.len()).find(|&p| state.bodies[p].is_some())
+    }
+
+    /// 所有蛇决策完毕后结算联合动作，末尾已死亡的蛇补上占位
+    fn resolve(state: &SimState, joint: &mut Vec<Action>) -> SimState {
+        joint.resize(state.bodies.len(), Action::Up);
+        let mut next = state.clone();
+        next.advance(joint);
+        next
+    }
+
+    /// 蛇 p 在本节点要展开的走法：第一步已知走法且合法时只保留这一个
+    fn moves(&self, state: &SimState, p: usize) -> Vec<Action> {
+        let moves = state.legal_moves().swap_remove(p);
+        match self.fixed.get(p).copied().flatten() {
+            Some(reply) if state.depth == 0 && moves.contains(&reply) => vec![reply],
+            _ => moves,
+        }
+    }
+
+    fn is_leaf(state: &SimState, depth: usize) -> bool {
+        depth == 0 || state.bodies[0].is_none() || state.bodies.iter().flatten().count() == 1
+    }
+
+    /// max-n：返回各蛇的份额；parent_best 为上一个决策者已经保证的份额，用于浅层剪枝
+    fn maxn(
+        &mut self,
+        state: &SimState,
+        joint: &mut Vec<Action>,
+        player: usize,
+        depth: usize,
+        parent_best: f64,
+        deadline: &Deadline,
+    ) -> Option<Vec<f64>> {
+        self.nodes += 1;
+        if deadline.expired() {
+            return None;
+        }
+        let Some(p) = Self::next_player(state, player) else {
+            let next = Self::resolve(state, joint);
+            return if Self::is_leaf(&next, depth - 1) {
+                Some(shares(&next))
+            } else {
+                self.maxn(&next, &mut Vec::new(), 0, depth - 1, f64::NEG_INFINITY, deadline)
+            };
+        };
+        // 已死亡的蛇占位
+        joint.resize(p, Action::Up);
+        let moves = self.moves(state, p);
+        let mut best: Option<Vec<f64>> = None;
+        for action in moves {
+            joint.push(action);
+            let best_p = best.as_ref().map_or(f64::NEG_INFINITY, |v| v[p]);
+            let value = self.maxn(state, joint, p + 1, depth, best_p, deadline);
+            joint.truncate(p);
+            let value = value?;
+            if best.as_ref().is_none_or(|b| value[p] > b[p]) {
+                best = Some(value);
+            }
+            // 份额总和为 1：当前蛇已拿到的份额使上一个决策者不可能超过 parent_best，剪枝
+            if best.as_ref().unwrap()[p] >= 1.0 - parent_best {
+                break;
+            }
+        }
+        best
+    }
+
+    /// paranoid：返回我方份额，我方取最大，敌蛇取最小
+    #[allow(clippy::too_many_arguments)]
+    fn paranoid(
+        &mut self,
+        state: &SimState,
+        joint: &mut Vec<Action>,
+        player: usize,
+        depth: usize,
+        mut alpha: f64,
+        mut beta: f64,
+        deadline: &Deadline,
+    ) -> Option<f64> {
+        self.nodes += 1;
+        if deadline.expired() {
+            return None;
+        }
+        let Some(p) = Self::next_player(state, player) else {
+            let next = Self::resolve(state, joint);
+            return if Self::is_leaf(&next, depth - 1) {
+                Some(shares(&next)[0])
+            } else {
+                self.paranoid(&next, &mut Vec::new(), 0, depth - 1, alpha, beta, deadline)
+            };
+        };
+        joint.resize(p, Action::Up);
+        let moves = self.moves(state, p);
+        let maximizing = p == 0;
+        let mut best = if maximizing { f64::NEG_INFINITY } else { f64::INFINITY };
+        for action in moves {
+            joint.push(action);
+            let value = self.paranoid(state, joint, p + 1, depth, alpha, beta, deadline);
+            joint.truncate(p);
+            let value = value?;
+            if maximizing {
+                best = best.max(value);
+                alpha = alpha.max(best);
+            } else {
+                best = best.min(value);
+                beta = beta.min(best);
+            }
+            if alpha >= beta {
+                break;
+            }
+        }
+        Some(best)
+    }
+}
+
+impl DepthLimitedSearch for MultiplayerSearch {
+    fn name(&self) -> &'static str {
+        match self.mode {
+            SearchMode::MaxN => "maxn",
+            SearchMode::Paranoid => "paranoid",
+        }
+    }
+
+    fn search(&mut self, view: &BoardView, depth: usize, deadline: &Deadline) -> Option<(Action, f64)> {
+        let root = SimState::from_view(view);
+        self.nodes = 0;
+        self.fixed = std::iter::once(None)
+            .chain(
+                view.others
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, body)| !body.is_empty())
+                    .map(|(i, _)| self.known.get(i).copied().flatten()),
+            )
+            .collect();
+        let mut best: Option<(Action, f64)> = None;
+        for action in root.legal_moves().swap_remove(0) {
+            let mut joint = vec![action];
+            let value = match self.mode {
+                SearchMode::MaxN => {
+                    let parent_best = best.map_or(f64::NEG_INFINITY, |(_, v)| v);
+                    self.maxn(&root, &mut joint, 1, depth, parent_best, deadline)?[0]
+                }
+                SearchMode::Paranoid => {
+                    let alpha = best.map_or(f64::NEG_INFINITY, |(_, v)| v);
+                    self.paranoid(&root, &mut joint, 1, depth, alpha, f64::INFINITY, deadline)?
+                }
+            };
+            if best.is_none_or(|(_, b)| value > b) {
+                best = Some((action, value));
+            }
+        }
+        best
+    }
+
+    fn plan_against(&mut self, known: &[Option<Action>]) {
+        self.known = known.to_vec();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn long_deadline() -> Deadline {
+        Deadline::after(10_000.0)
+    }
+
+    // 4 蛇模式：我方 (3,3) 向上或向右都可能与敌蛇蛇头相撞，只有向左的 (2,3) 没有风险
+    fn crowded_view() -> BoardView {
+        let me = [3, 3, 3, 2, 3, 1, 4, 1];
+        let others = [
+            4, 4, 5, 4, 6, 4, 7, 4, //
+            1, 5, 1, 6, 1, 7, 1, 8, //
+            4, 2, 5, 2, 6, 2, 7, 2,
+        ];
+        BoardView::parse(8, &me, 3, &others, &[8, 8], 100)
+    }
+
+    // 测试1：paranoid 假设敌蛇会同归于尽，选择没有相撞风险的方向
+    #[test]
+    fn test_paranoid_avoids_head_on() {
+        let mut search = MultiplayerSearch::new(SearchMode::Paranoid);
+        let (action, _) = search.search(&crowded_view(), 2, &long_deadline()).unwrap();
+        assert_eq!(action, Action::Left);
+    }
+
+    // 测试2：max-n 能看出下一回合必死的死路
+    #[test]
+    fn test_maxn_avoids_dead_end() {
+        // 向上吃到 (1,5) 的果子后被敌蛇、墙和自己的身体围住
+        let view = BoardView::parse(5, &[1, 4, 1, 3, 1, 2, 1, 1], 1, &[2, 5, 3, 5, 3, 4, 3, 3], &[1, 5], 50);
+        let mut search = MultiplayerSearch::new(SearchMode::MaxN);
+        let (action, _) = search.search(&view, 2, &long_deadline()).unwrap();
+        assert_eq!(action, Action::Right);
+    }
+
+    // 测试3：份额总和为 1，死亡的蛇份额极小
+    #[test]
+    fn test_shares_sum_to_one() {
+        let mut state = SimState::from_view(&crowded_view());
+        let values = shares(&state);
+        assert!((values.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+        state.bodies[1] = None;
+        assert!(shares(&state)[1] < 0.01);
+    }
+
+    // 测试4：时间耗尽时放弃本层
+    #[test]
+    fn test_deadline_aborts() {
+        let mut search = MultiplayerSearch::new(SearchMode::MaxN);
+        assert!(search.search(&crowded_view(), 3, &Deadline::after(0.0)).is_none());
+    }
+
+    // 测试5：记忆中已知敌蛇的走法时不再对它分支：已知两条敌蛇会离开，向右不再有相撞风险
+    #[test]
+    fn test_known_replies_fix_root_moves() {
+        let mut search = MultiplayerSearch::new(SearchMode::Paranoid);
+        search.plan_against(&[Some(Action::Up), None, Some(Action::Down)]);
+        let (action, _) = search.search(&crowded_view(), 2, &long_deadline()).unwrap();
+        assert_eq!(action, Action::Right);
+    }
+}
@@ -0,0 +1,291 @@
+//! 解耦 UCT 蒙特卡洛树搜索：所有蛇同时移动，每个节点为每条蛇各维护一组多臂老虎机统计，
+//! 各蛇独立按 UCB1 选择动作，组合成联合动作后进入子节点。
+
+use board_engine::{Action, Pos};
+use std::collections::HashMap;
+
+use crate::clock::Deadline;
+use crate::greedy::{compute_survival_score, ESCAPE_HORIZON};
+use crate::sim::SimState;
+use crate::strategy::{step, BoardView, SnakeStrategy, XorShift};
+
+/// 单步时间预算上限：引擎限时 500ms，为解析与引擎开销留出余量
+pub const MAX_TIME_BUDGET_MS: f64 = 400.0;
+
+/// 模拟阶段的走子方式
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rollout {
+    /// 在安全方向中均匀随机
+    Random,
+    /// 优先吃果子、靠近果子、远离敌蛇蛇头，截断时用生存得分评估死路
+    Heuristic,
+}
+
+#[derive(Clone, Debug)]
+pub struct MctsConfig {
+    /// 每步搜索的时间预算（毫秒），超过 MAX_TIME_BUDGET_MS 时按上限处理
+    pub time_budget_ms: f64,
+    /// 迭代次数上限，主要用于测试时获得确定的结果
+    pub max_iterations: usize,
+    /// UCB1 的探索系数
+    pub exploration: f64,
+    /// 从根节点起最多模拟的回合数
+    pub max_depth: usize,
+    pub rollout: Rollout,
+}
+
+impl Default for MctsConfig {
+    fn default() -> Self {
+        MctsConfig {
+            time_budget_ms: 100.0,
+            max_iterations: usize::MAX,
+            exploration: 1.0,
+            max_depth: 12,
+            rollout: Rollout::Heuristic,
+        }
+    }
+}
+
+/// 每条蛇的收益，取值 [0,1]：存活占一半，吃到的果子按边际递减计入另一半
+fn rewards(state: &SimState, evaluate_traps: bool) -> Vec<f64> {
+    (0..state.bodies.len())
+        .map(|i| {
+            let food = 1.0 - 0.5f64.powi(state.eaten[i] as i32);
+            let alive = match &state.bodies[i] {
+                None => 0.0,
+                Some(body) if evaluate_traps => {
+                    let others: Vec<Vec<Pos>> = state
+                        .bodies
+                        .iter()
+                        .enumerate()
+                        .filter(|&(j, _)| j != i)
+                        .filter_map(|(_, b)| b.clone())
+                        .collect();
+                    // 生存得分为负说明已走进死路，存活收益减半
+                    let survival = compute_survival_score(body[0], body, &others, state.n, body.len(), ESCAPE_HORIZON);
+                    if survival < 0.0 { 0.25 } else { 0.5 }
+                }
+                Some(_) => 0.5,
+            };
+            alive + 0.5 * food
+        })
+        .collect()
+}
+
+/// 搜索树节点，stats[s][k] 为第 s 条蛇第 k 个合法方向的 (访问次数, 累计收益)
+struct Node {
+    visits: u32,
+    legal: Vec<Vec<Action>>,
+    stats: Vec<Vec<(u32, f64)>>,
+    children: HashMap<Vec<Action>, usize>,
+}
+
+impl Node {
+    fn new(state: &SimState) -> Node {
+        let legal = state.legal_moves();
+        let stats = legal.iter().map(|moves| vec![(0, 0.0); moves.len()]).collect();
+        Node { visits: 0, legal, stats, children: HashMap::new() }
+    }
+}
+
+/// 解耦 UCT 策略
+pub struct MctsStrategy {
+    config: MctsConfig,
+    rng: XorShift,
+    nodes: Vec<Node>,
+}
+
+impl Default for MctsStrategy {
+    fn default() -> Self {
+        MctsStrategy::new(MctsConfig::default())
+    }
+}
+
+impl MctsStrategy {
+    pub fn new(config: MctsConfig) -> MctsStrategy {
+        MctsStrategy { config, rng: XorShift::default(), nodes: Vec::new() }
+    }
+
+    /// 每条蛇独立选择：先尝试未访问过的方向，之后按 UCB1 选择
+    fn select(&mut self, node: usize) -> Vec<Action> {
+        let exploration = self.config.exploration;
+        let node = &self.nodes[node];
+        let log_visits = (node.visits.max(1) as f64).ln();
+        let mut joint = Vec::with_capacity(node.legal.len());
+        for (moves, stats) in node.legal.iter().zip(&node.stats) {
+            if moves.is_empty() {
+                // 已死亡的蛇占位，不参与移动
+                joint.push(Action::Up);
+                continue;
+            }
+            let untried: Vec<usize> = (0..moves.len()).filter(|&k| stats[k].0 == 0).collect();
+            let pick = if !untried.is_empty() {
+                untried[self.rng.below(untried.len())]
+            } else {
+                (0..moves.len())
+                    .max_by(|&a, &b| {
+                        let ucb = |k: usize| {
+                            let (visits, total) = stats[k];
+                            total / visits as f64 + exploration * (log_visits / visits as f64).sqrt()
+                        };
+                        ucb(a).total_cmp(&ucb(b))
+                    })
+                    .unwrap()
+            };
+            joint.push(moves[pick]);
+        }
+        joint
+    }
+
+    fn rollout_action(&mut self, state: &SimState, snake: usize, moves: &[Action]) -> Action {
+        if self.config.rollout == Rollout::Random || moves.len() == 1 || self.rng.unit() < 0.1 {
+            return moves[self.rng.below(moves.len())];
+        }
+        let head = state.bodies[snake].as_ref().unwrap()[0];
+        let enemy_heads: Vec<Pos> = state
+            .bodies
+            .iter()
+            .enumerate()
+            .filter(|&(j, _)| j != snake)
+            .filter_map(|(_, b)| b.as_ref().map(|b| b[0]))
+            .collect();
+        let score = |action: Action| {
+            let next = step(head, action);
+            let food_dist = state
+                .foods
+                .iter()
+                .map(|&(fx, fy)| (fx - next.0).abs() + (fy - next.1).abs())
+                .min()
+                .unwrap_or(0);
+            let head_on = enemy_heads.iter().any(|&(hx, hy)| (hx - next.0).abs() + (hy - next.1).abs() == 1);
+            -food_dist - if head_on { 3 } else { 0 }
+        };
+        moves.iter().copied().max_by_key(|&a| score(a)).unwrap()
+    }
+
+    fn rollout(&mut self, mut state: SimState, horizon: usize) -> Vec<f64> {
+        while state.depth < horizon && state.bodies[0].is_some() {
+            let legal = state.legal_moves();
+            let joint: Vec<Action> = legal
+                .iter()
+                .enumerate()
+                .map(|(s, moves)| if moves.is_empty() { Action::Up } else { self.rollout_action(&state, s, moves) })
+                .collect();
+            state.advance(&joint);
+        }
+        rewards(&state, self.config.rollout == Rollout::Heuristic && state.depth >= horizon)
+    }
+
+    fn iterate(&mut self, root: &SimState, horizon: usize) {
+        let mut state = root.clone();
+        let mut node = 0;
+        let mut path: Vec<(usize, Vec<Action>)> = Vec::new();
+        while state.depth < horizon && state.bodies[0].is_some() {
+            let joint = self.select(node);
+            state.advance(&joint);
+            path.push((node, joint.clone()));
+            match self.nodes[node].children.get(&joint) {
+                Some(&child) => node = child,
+                None => {
+                    let child = self.nodes.len();
+                    self.nodes.push(Node::new(&state));
+                    self.nodes[node].children.insert(joint, child);
+                    break;
+                }
+            }
+        }
+        let rewards = self.rollout(state, horizon);
+        for (node, joint) in path {
+            let node = &mut self.nodes[node];
+            node.visits += 1;
+            for (s, action) in joint.iter().enumerate() {
+                if let Some(k) = node.legal[s].iter().position(|a| a == action) {
+                    node.stats[s][k].0 += 1;
+                    node.stats[s][k].1 += rewards[s];
+                }
+            }
+        }
+    }
+
+    /// 搜索并返回根节点上我方访问次数最多的方向，以及完成的迭代次数
+    pub fn search(&mut self, view: &BoardView) -> (Action, usize) {
+        let deadline = Deadline::after(self.config.time_budget_ms.min(MAX_TIME_BUDGET_MS));
+        let root = SimState::from_view(view);
+        let horizon = self.config.max_depth.min(view.round.max(1) as usize);
+        self.nodes.clear();
+        self.nodes.push(Node::new(&root));
+        if self.nodes[0].legal[0].len() == 1 {
+            return (self.nodes[0].legal[0][0], 0);
+        }
+        let mut iterations = 0;
+        while iterations < self.config.max_iterations && (iterations == 0 || !deadline.expired()) {
+            self.iterate(&root, horizon);
+            iterations += 1;
+        }
+        let root = &self.nodes[0];
+        let best = (0..root.legal[0].len())
+            .max_by(|&a, &b| {
+                let (va, ta) = root.stats[0][a];
+                let (vb, tb) = root.stats[0][b];
+                va.cmp(&vb).then((ta / va.max(1) as f64).total_cmp(&(tb / vb.max(1) as f64)))
+            })
+            .unwrap();
+        (root.legal[0][best], iterations)
+    }
+}
+
+impl SnakeStrategy for MctsStrategy {
+    fn name(&self) -> &'static str {
+        "mcts"
+    }
+
+    fn choose(&mut self, view: &BoardView) -> Action {
+        self.search(view).0
+    }
+
+    fn reset(&mut self) {
+        self.rng = XorShift::default();
+        self.nodes.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixed(iterations: usize) -> MctsStrategy {
+        MctsStrategy::new(MctsConfig { max_iterations: iterations, time_budget_ms: MAX_TIME_BUDGET_MS, ..MctsConfig::default() })
+    }
+
+    // 测试1：模拟的碰撞规则与引擎一致：蛇头相撞同归于尽，吃到果子后果子消失
+    #[test]
+    fn test_sim_collisions() {
+        let view = BoardView::parse(5, &[2, 3, 1, 3, 1, 2, 1, 1], 1, &[4, 3, 5, 3, 5, 2, 5, 1], &[1, 4], 50);
+        let mut state = SimState::from_view(&view);
+        state.advance(&[Action::Right, Action::Left]);
+        assert!(state.bodies.iter().all(|b| b.is_none()));
+
+        let mut state = SimState::from_view(&view);
+        state.advance(&[Action::Up, Action::Up]);
+        assert_eq!(state.bodies[0].as_ref().unwrap()[0], (2, 4));
+        let mut state = SimState::from_view(&view);
+        state.advance(&[Action::Left, Action::Down]);
+        assert!(state.bodies[0].is_none());
+    }
+
+    // 测试2：果子就在旁边且没有危险时，选择吃果子的方向
+    #[test]
+    fn test_takes_safe_food() {
+        // 我方在 (3,3)，向上 (3,4) 有果子；敌蛇在右下角远处
+        let view = BoardView::parse(5, &[3, 3, 3, 2, 2, 2, 1, 2], 1, &[5, 1, 4, 1, 3, 1, 2, 1], &[3, 4], 50);
+        assert_eq!(fixed(3000).choose(&view), Action::Up);
+    }
+
+    // 测试3：不会为了果子走进下一回合必死的角落
+    #[test]
+    fn test_avoids_dead_end() {
+        // 向上吃到 (1,5) 的果子后被敌蛇、墙和自己的身体围住
+        let view = BoardView::parse(5, &[1, 4, 1, 3, 1, 2, 1, 1], 1, &[2, 5, 3, 5, 3, 4, 3, 3], &[1, 5], 50);
+        assert_eq!(fixed(5000).choose(&view), Action::Right);
+    }
+}
@@ -0,0 +1,149 @@
+//! 记忆确定性对手：大多数课程机器人的走法只由棋盘决定。
+//! 把每条敌蛇的局部视野（自身蛇体、附近的果子与附近的其他蛇）哈希为键，记下它在该视野下实际的走法；
+//! 视野使用相对蛇头的坐标，加上到四面墙的距离，同一局面平移到棋盘别处时视为同一视野；
+//! 同一视野再次出现时直接给出这一走法，并记录它被再次证实的次数：局部视野不一定包含决定走法的全部信息，
+//! 使用者可以只完全相信多次证实过的条目。同一视野出现过不同走法的条目不再用于预测。
+//! 每条敌蛇的记忆按编号分开保存并限制条目数，新局时随对手模型一起清空。
+
+use board_engine::{Action, Pos};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
+use std::hash::{Hash, Hasher};
+
+use crate::strategy::BoardView;
+use crate::tracker::EnemyId;
+
+/// 局部视野的半径（曼哈顿距离），超出范围的果子与蛇身不计入视野
+pub const VIEW_RADIUS: i32 = 2;
+
+/// 每条敌蛇最多记忆的视野数，超出后淘汰最早记下的
+pub const CAPACITY: usize = 4096;
+
+#[derive(Clone, Copy, Debug)]
+struct Entry {
+    action: Action,
+    // 同一视野下是否始终是同一个走法
+    consistent: bool,
+    // 记下之后再次看到同一走法的次数
+    confirmations: u32,
+}
+
+#[derive(Clone, Debug, Default)]
+struct Table {
+    entries: HashMap<u64, Entry>,
+    // 记下的先后顺序，用于淘汰
+    order: VecDeque<u64>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct MoveMemo {
+    tables: HashMap<EnemyId, Table>,
+}
+
+// 视野外的格子
+const OUTSIDE: Pos = (i32::MAX, i32::MAX);
+
+/// view.others[enemy] 的局部视野的哈希值：相对蛇头的蛇体、到四面墙的距离（超过 VIEW_RADIUS 的按 VIEW_RADIUS + 1 计），
+/// 以及以蛇头为中心 VIEW_RADIUS 内的果子与其他蛇的格子
+pub fn view_key(view: &BoardView, enemy: usize) -> u64 {
+    let body = &view.others[enemy];
+    let (hx, hy) = body[0];
+    let near = |&(x, y): &Pos| (x - hx).abs() + (y - hy).abs() <= VIEW_RADIUS;
+    let relative = |(x, y): Pos| (x - hx, y - hy);
+    let walls = [hx - 1, hy - 1, view.n - hx, view.n - hy].map(|d| d.min(VIEW_RADIUS + 1));
+    let shape: Vec<Pos> = body.iter().map(|&cell| if near(&cell) { relative(cell) } else { OUTSIDE }).collect();
+    let mut foods: Vec<Pos> = view.foods.iter().copied().filter(near).map(relative).collect();
+    foods.sort_unstable();
+    // 其他蛇按蛇分组，蛇内保持从头到尾的顺序，组之间排序，与输入顺序无关
+    let mut snakes: Vec<Vec<Pos>> = std::iter::once(&view.me)
+        .chain(view.others.iter().enumerate().filter(|&(i, _)| i != enemy).map(|(_, body)| body))
+        .filter(|body| body.iter().any(near))
+        .map(|body| body.iter().map(|&cell| if near(&cell) { relative(cell) } else { OUTSIDE }).collect())
+        .collect();
+    snakes.sort_unstable();
+
+    let mut hasher = DefaultHasher::new();
+    walls.hash(&mut hasher);
+    shape.hash(&mut hasher);
+    foods.hash(&mut hasher);
+    snakes.hash(&mut hasher);
+    hasher.finish()
+}
+
+impl MoveMemo {
+    /// 记下编号为 id 的敌蛇在视野 key 下实际的走法
+    pub fn record(&mut self, id: EnemyId, key: u64, action: Action) {
+        let table = self.tables.entry(id).or_default();
+        if let Some(entry) = table.entries.get_mut(&key) {
+            if entry.action == action {
+                entry.confirmations += 1;
+            } else {
+                entry.consistent = false;
+            }
+            return;
+        }
+        if table.order.len() >= CAPACITY {
+            if let Some(oldest) = table.order.pop_front() {
+                table.entries.remove(&oldest);
+            }
+        }
+        table.entries.insert(key, Entry { action, consistent: true, confirmations: 0 });
+        table.order.push_back(key);
+    }
+
+    /// 编号为 id 的敌蛇在视野 key 下记住的 (走法, 被再次证实的次数)；没有见过或走法不一致时返回 None
+    pub fn lookup(&self, id: EnemyId, key: u64) -> Option<(Action, u32)> {
+        let entry = self.tables.get(&id)?.entries.get(&key)?;
+        entry.consistent.then_some((entry.action, entry.confirmations))
+    }
+
+    /// 编号为 id 的敌蛇记忆的视野数
+    pub fn len(&self, id: EnemyId) -> usize {
+        self.tables.get(&id).map_or(0, |table| table.entries.len())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tables.values().all(|table| table.entries.is_empty())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 测试1：视野与其他蛇的输入顺序和远处的果子无关，附近的果子改变时视野不同；远离墙壁时与位置无关
+    #[test]
+    fn test_view_key() {
+        let me = [1, 1, 1, 2, 1, 3, 1, 4];
+        let enemy = [5, 5, 5, 6, 5, 7, 5, 8];
+        let third = [8, 1, 7, 1, 6, 1, 5, 1];
+        let a = BoardView::parse(10, &me, 2, &[enemy, third].concat(), &[6, 5, 10, 10], 80);
+        let b = BoardView::parse(10, &me, 2, &[third, enemy].concat(), &[10, 1, 6, 5], 60);
+        assert_eq!(view_key(&a, 0), view_key(&b, 1));
+        let c = BoardView::parse(10, &me, 2, &[enemy, third].concat(), &[4, 5, 10, 10], 80);
+        assert_ne!(view_key(&a, 0), view_key(&c, 0));
+        let d = BoardView::parse(12, &me, 1, &[6, 6, 6, 7, 6, 8, 6, 9], &[7, 6], 80);
+        let e = BoardView::parse(12, &me, 1, &[7, 6, 7, 7, 7, 8, 7, 9], &[8, 6], 80);
+        assert_eq!(view_key(&d, 0), view_key(&e, 0));
+    }
+
+    // 测试2：同一走法累计证实次数，出现不同走法后不再预测；超出容量时淘汰最早的条目
+    #[test]
+    fn test_record_and_evict() {
+        let mut memo = MoveMemo::default();
+        memo.record(0, 7, Action::Up);
+        assert_eq!(memo.lookup(0, 7), Some((Action::Up, 0)));
+        memo.record(0, 7, Action::Up);
+        assert_eq!(memo.lookup(0, 7), Some((Action::Up, 1)));
+        assert_eq!(memo.lookup(1, 7), None);
+        memo.record(0, 7, Action::Left);
+        assert_eq!(memo.lookup(0, 7), None);
+
+        for key in 100..=100 + CAPACITY as u64 {
+            memo.record(1, key, Action::Down);
+        }
+        assert_eq!(memo.len(1), CAPACITY);
+        assert_eq!(memo.lookup(1, 100), None);
+        assert_eq!(memo.lookup(1, 101), Some((Action::Down, 0)));
+    }
+}
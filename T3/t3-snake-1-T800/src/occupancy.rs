@@ -0,0 +1,129 @@
+//! 随时间变化的占用：蛇不会变长，蛇体第 k 节（蛇头为 0）在 L-k 回合后必然腾出。
+//! 据此判断某个格子在第 t 回合能否进入，并计算考虑腾出时间的可活动区域。
+
+use board_engine::{Action, Pos};
+
+use crate::strategy::step;
+
+#[derive(Clone, Debug)]
+pub struct Occupancy {
+    n: i32,
+    // free_at[idx]：该格子从第几回合起可以进入，0 表示当前为空
+    free_at: Vec<u32>,
+}
+
+impl Occupancy {
+    pub fn new(n: i32) -> Occupancy {
+        Occupancy { n, free_at: vec![0; (n * n) as usize] }
+    }
+
+    /// 由当前（第 0 回合）的所有蛇体构建
+    pub fn from_bodies<'a>(n: i32, bodies: impl IntoIterator<Item = &'a Vec<Pos>>) -> Occupancy {
+        let mut occupancy = Occupancy::new(n);
+        for body in bodies {
+            occupancy.add_snake(body, 0);
+        }
+        occupancy
+    }
+
+    pub fn n(&self) -> i32 {
+        self.n
+    }
+
+    pub fn in_bounds(&self, (x, y): Pos) -> bool {
+        x >= 1 && y >= 1 && x <= self.n && y <= self.n
+    }
+
+    fn index(&self, (x, y): Pos) -> usize {
+        ((y - 1) * self.n + (x - 1)) as usize
+    }
+
+    /// 加入一条在第 turn 回合时位于 body 的蛇：第 k 节在第 turn + L - k 回合腾出
+    pub fn add_snake(&mut self, body: &[Pos], turn: u32) {
+        let len = body.len() as u32;
+        for (k, &cell) in body.iter().enumerate() {
+            if self.in_bounds(cell) {
+                let idx = self.index(cell);
+                self.free_at[idx] = self.free_at[idx].max(turn + len - k as u32);
+            }
+        }
+    }
+
+    /// 格子从第几回合起可以进入
+    pub fn free_at(&self, cell: Pos) -> u32 {
+        self.free_at[self.index(cell)]
+    }
+
+    /// 第 turn 回合能否进入该格子
+    pub fn can_enter(&self, cell: Pos, turn: u32) -> bool {
+        self.in_bounds(cell) && self.free_at(cell) <= turn
+    }
+
+    /// 从第 turn 回合位于 start 出发做广度优先搜索，
+    /// 每走一步回合数加一，只进入到达时已经腾出的格子；返回每个格子的最早到达回合
+    pub fn arrival_turns(&self, start: Pos, turn: u32) -> Vec<Option<u32>> {
+        let mut arrival = vec![None; self.free_at.len()];
+        if !self.in_bounds(start) {
+            return arrival;
+        }
+        arrival[self.index(start)] = Some(turn);
+        let mut frontier = vec![start];
+        let mut t = turn;
+        while !frontier.is_empty() {
+            t += 1;
+            let mut next_frontier = Vec::new();
+            for cell in frontier {
+                for action in Action::ALL {
+                    let next = step(cell, action);
+                    if self.can_enter(next, t) && arrival[self.index(next)].is_none() {
+                        arrival[self.index(next)] = Some(t);
+                        next_frontier.push(next);
+                    }
+                }
+            }
+            frontier = next_frontier;
+        }
+        arrival
+    }
+
+    /// 考虑腾出时间的可活动区域大小（包括起点）
+    pub fn reachable_area(&self, start: Pos, turn: u32) -> usize {
+        self.arrival_turns(start, turn).iter().flatten().count()
+    }
+
+    /// 能否在格子腾出后到达 target
+    pub fn can_reach(&self, start: Pos, turn: u32, target: Pos) -> bool {
+        self.in_bounds(target) && self.arrival_turns(start, turn)[self.index(target)].is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 测试1：蛇尾下一回合腾出，蛇头要等 L 回合
+    #[test]
+    fn test_free_at() {
+        let body = vec![(1, 4), (1, 3), (1, 2), (1, 1)];
+        let occupancy = Occupancy::from_bodies(5, [&body]);
+        assert_eq!(occupancy.free_at((1, 1)), 1);
+        assert_eq!(occupancy.free_at((1, 4)), 4);
+        assert_eq!(occupancy.free_at((3, 3)), 0);
+        assert!(occupancy.can_enter((1, 1), 1));
+        assert!(!occupancy.can_enter((1, 2), 1));
+    }
+
+    // 测试2：敌蛇身后的格子按腾出顺序依次打开
+    #[test]
+    fn test_area_opens_behind_tail() {
+        // 3x3 棋盘，敌蛇 (3,1)->(3,2)->(2,2)->(2,1)，我方从 (1,1) 出发；静态看只有 5 格
+        let enemy = vec![(3, 1), (3, 2), (2, 2), (2, 1)];
+        let occupancy = Occupancy::from_bodies(3, [&enemy]);
+        assert_eq!(occupancy.reachable_area((1, 1), 0), 9);
+        assert!(occupancy.can_reach((1, 1), 0, (3, 1)));
+        // 蛇不能原地等待：四周的蛇身都来不及腾出时只剩起点
+        let enemy = vec![(1, 2), (2, 2), (2, 1), (3, 1)];
+        let occupancy = Occupancy::from_bodies(3, [&enemy]);
+        assert_eq!(occupancy.reachable_area((1, 1), 0), 1);
+    }
+}
@@ -0,0 +1,262 @@
+//! 在线对手建模：对每条跟踪中的敌蛇，用几种候选模型（最近果子 BFS、随机安全方向、空间最大化、
+//! 从敌蛇视角运行我方的加权启发式）预测它的下一步，按每个模型对实际走法的预测概率更新后验权重，
+//! 以后验加权的混合分布作为敌蛇下一步的概率，供蛇头相撞风险与果子争夺使用。
+//! 敌蛇的局部视野与记忆中的某次观察完全相同时，直接采用它当时的走法（见 memo 模块）；
+//! 局部视野可能漏掉远处决定走法的果子，被证实 CONFIRMATIONS 次之前仍按普通预测与均匀分布混合。
+
+use board_engine::{Action, Pos};
+use std::collections::HashMap;
+
+use crate::danger::{enemy_legal_moves, EnemyMoveModel};
+use crate::greedy::GreedyStrategy;
+use crate::memo::{view_key, MoveMemo};
+use crate::strategy::{step, BoardView, NearestFood, SnakeStrategy, SpaceMaximizer};
+use crate::tracker::EnemyId;
+
+/// 候选模型的名称，与后验权重的下标一致
+pub const MODEL_NAMES: [&str; 4] = ["nearest-food", "random", "space", "mirror"];
+
+// 确定性模型的预测与均匀分布的混合比例，避免一次预测失误就把后验清零
+const EPSILON: f32 = 0.1;
+
+// 旧证据的衰减：对手改变打法时后验能重新调整
+const DECAY: f32 = 0.9;
+
+// 我方启发式较慢，存活的敌蛇不超过该数量时才从敌蛇视角运行
+const MIRROR_MAX_ENEMIES: usize = 3;
+
+// 用作相撞风险时与均匀分布混合的比例：完全相信预测会让我方贴着敌蛇蛇头走，得不偿失
+const RISK_BLEND: f32 = 0.5;
+
+// 记忆命中时仍留给其他方向的概率
+const KNOWN_EPSILON: f32 = 0.02;
+
+// 记忆中的走法被再次证实这么多次后才视为已知走法
+const CONFIRMATIONS: u32 = 2;
+
+/// 概率不低于该值的方向才视为敌蛇可能的走法
+pub const PLAUSIBLE: f32 = 0.2;
+
+/// 一条敌蛇下一步的概率分布，probs 与 legal 一一对应
+#[derive(Clone, Debug, PartialEq)]
+pub struct MovePrediction {
+    pub legal: Vec<Action>,
+    pub probs: Vec<f32>,
+    /// 来自记忆且已多次证实的已知走法
+    pub known: bool,
+}
+
+// 上一回合的预测，等本回合看到敌蛇的实际走法后检验
+struct Pending {
+    head: Pos,
+    // 预测时敌蛇的局部视野
+    key: u64,
+    legal: Vec<Action>,
+    // 每个模型的预测，本回合没有运行的模型为 None
+    per_model: Vec<Option<Vec<f32>>>,
+}
+
+/// 按敌蛇编号保存的后验权重（取对数）与待检验的预测
+#[derive(Default)]
+pub struct OpponentModels {
+    log_weights: HashMap<EnemyId, [f32; MODEL_NAMES.len()]>,
+    pending: HashMap<EnemyId, Pending>,
+    memo: MoveMemo,
+}
+
+/// 确定性模型：把 1 - epsilon 的概率放在选中的方向上，其余均匀分配
+fn deterministic(choice: Action, legal: &[Action], epsilon: f32) -> Vec<f32> {
+    let uniform = 1.0 / legal.len() as f32;
+    if !legal.contains(&choice) {
+        return vec![uniform; legal.len()];
+    }
+    legal.iter().map(|&a| if a == choice { 1.0 - epsilon + epsilon * uniform } else { epsilon * uniform }).collect()
+}
+
+impl OpponentModels {
+    /// 用本回合存活敌蛇的 (编号, 蛇头) 检验上一回合的预测，更新后验
+    pub fn observe(&mut self, enemies: &[(EnemyId, Pos)]) {
+        for &(id, head) in enemies {
+            let Some(pending) = self.pending.get(&id) else {
+                continue;
+            };
+            let Some(taken) = pending.legal.iter().position(|&a| step(pending.head, a) == head) else {
+                continue;
+            };
+            self.memo.record(id, pending.key, pending.legal[taken]);
+            let uniform = 1.0 / pending.legal.len() as f32;
+            let weights = self.log_weights.entry(id).or_insert([0.0; MODEL_NAMES.len()]);
+            for (weight, prediction) in weights.iter_mut().zip(&pending.per_model) {
+                // 没有运行的模型按均匀分布计，不奖励也不惩罚太多
+                let p = prediction.as_ref().map_or(uniform, |probs| probs[taken]);
+                *weight = DECAY * *weight + p.ln();
+            }
+        }
+        self.pending.clear();
+    }
+
+    /// 编号为 id 的敌蛇对各模型的后验概率，与 MODEL_NAMES 对应；没有观察过时为均匀分布
+    pub fn posterior(&self, id: EnemyId) -> [f32; MODEL_NAMES.len()] {
+        let weights = self.log_weights.get(&id).copied().unwrap_or_default();
+        let max = weights.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+        let exp = weights.map(|w| (w - max).exp());
+        let total: f32 = exp.iter().sum();
+        exp.map(|w| w / total)
+    }
+
+    /// 预测 view 中每条存活敌蛇的下一步；ids 与 view.alive_others() 一一对应
+    pub fn predict(&mut self, view: &BoardView, ids: &[EnemyId]) -> Prediction {
+        let blocked = view.blocked_next_turn();
+        let alive = view.alive_others().count();
+        let mut moves = HashMap::new();
+        let enemies = view.others.iter().enumerate().filter(|(_, body)| !body.is_empty());
+        for ((enemy, body), &id) in enemies.zip(ids) {
+            let legal = enemy_legal_moves(view, enemy, &blocked);
+            if legal.is_empty() {
+                continue;
+            }
+            let key = view_key(view, enemy);
+            // 记忆命中：不再运行候选模型，本回合也不更新后验
+            if let Some((action, confirmations)) = self.memo.lookup(id, key).filter(|(a, _)| legal.contains(a)) {
+                let probs = deterministic(action, &legal, KNOWN_EPSILON);
+                let known = confirmations >= CONFIRMATIONS;
+                moves.insert(enemy, MovePrediction { legal: legal.clone(), probs, known });
+                let per_model = vec![None; MODEL_NAMES.len()];
+                self.pending.insert(id, Pending { head: body[0], key, legal, per_model });
+                continue;
+            }
+            let seen = view.seen_by(enemy);
+            let per_model = vec![
+                Some(deterministic(NearestFood.choose(&seen), &legal, EPSILON)),
+                Some(vec![1.0 / legal.len() as f32; legal.len()]),
+                Some(deterministic(SpaceMaximizer.choose(&seen), &legal, EPSILON)),
+                (alive <= MIRROR_MAX_ENEMIES).then(|| deterministic(GreedyStrategy::mirror().choose(&seen), &legal, EPSILON)),
+            ];
+
+            // 只在运行了的模型之间按后验加权
+            let posterior = self.posterior(id);
+            let used: f32 = posterior.iter().zip(&per_model).filter(|(_, p)| p.is_some()).map(|(w, _)| w).sum();
+            let mut probs = vec![0.0; legal.len()];
+            for (&w, prediction) in posterior.iter().zip(&per_model) {
+                if let Some(prediction) = prediction {
+                    for (p, &q) in probs.iter_mut().zip(prediction) {
+                        *p += w / used * q;
+                    }
+                }
+            }
+            moves.insert(enemy, MovePrediction { legal: legal.clone(), probs, known: false });
+            self.pending.insert(id, Pending { head: body[0], key, legal, per_model });
+        }
+        Prediction { moves }
+    }
+
+    /// 确定性对手的走法记忆
+    pub fn memo(&self) -> &MoveMemo {
+        &self.memo
+    }
+}
+
+/// 本回合每条敌蛇（按 view.others 的下标）下一步的概率分布
+#[derive(Clone, Debug, Default)]
+pub struct Prediction {
+    moves: HashMap<usize, MovePrediction>,
+}
+
+impl Prediction {
+    pub fn get(&self, enemy: usize) -> Option<&MovePrediction> {
+        self.moves.get(&enemy)
+    }
+
+    /// 记忆中已知的走法
+    pub fn known_move(&self, enemy: usize) -> Option<Action> {
+        let prediction = self.get(enemy).filter(|prediction| prediction.known)?;
+        prediction.legal.iter().zip(&prediction.probs).max_by(|a, b| a.1.total_cmp(b.1)).map(|(&a, _)| a)
+    }
+
+    /// 概率不低于 PLAUSIBLE 的方向，没有预测时返回 None
+    pub fn plausible_moves(&self, enemy: usize) -> Option<Vec<Action>> {
+        self.get(enemy).map(|prediction| {
+            prediction.legal.iter().zip(&prediction.probs).filter(|&(_, &p)| p >= PLAUSIBLE).map(|(&a, _)| a).collect()
+        })
+    }
+}
+
+impl EnemyMoveModel for Prediction {
+    fn move_probabilities(&self, _view: &BoardView, enemy: usize, legal: &[Action]) -> Vec<f32> {
+        let Some(prediction) = self.get(enemy) else {
+            return vec![1.0 / legal.len() as f32; legal.len()];
+        };
+        let probs: Vec<f32> = legal
+            .iter()
+            .map(|a| prediction.legal.iter().position(|b| b == a).map_or(0.0, |i| prediction.probs[i]))
+            .collect();
+        let total: f32 = probs.iter().sum();
+        let uniform = 1.0 / legal.len() as f32;
+        // 已知的走法不再与均匀分布混合
+        let blend = if prediction.known { 0.0 } else { RISK_BLEND };
+        if total > 0.0 {
+            probs.iter().map(|p| (1.0 - blend) * p / total + blend * uniform).collect()
+        } else {
+            vec![uniform; legal.len()]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 测试1：一直走最近果子路线的敌蛇，后验集中到 nearest-food 模型，预测的概率随之集中
+    #[test]
+    fn test_learns_nearest_food_opponent() {
+        let mut models = OpponentModels::default();
+        // 敌蛇沿右边一列向上走向 (5,5) 的果子，我方在左边一列
+        let me = [1, 1, 1, 2, 1, 3, 1, 4];
+        let mut prediction = Prediction::default();
+        for y in 1..5 {
+            let enemy = [5, y, 4, y, 3, y, 2, y];
+            let enemy = if y == 1 { enemy } else { [5, y, 5, y - 1, 4, y - 1, 3, y - 1] };
+            let view = BoardView::parse(5, &me, 1, &enemy, &[5, 5], 50 - y);
+            models.observe(&[(0, (5, y))]);
+            prediction = models.predict(&view, &[0]);
+        }
+        let posterior = models.posterior(0);
+        assert!(posterior[0] > 2.0 * posterior[1], "{:?}", posterior);
+        assert_eq!(prediction.plausible_moves(0), Some(vec![Action::Up]));
+    }
+
+    // 测试2：作为相撞风险的模型时，分布与合法方向对齐、归一化并与均匀分布混合
+    #[test]
+    fn test_move_probabilities() {
+        let mut prediction = Prediction::default();
+        prediction.moves.insert(0, MovePrediction { legal: vec![Action::Up, Action::Left], probs: vec![0.75, 0.25], known: false });
+        let view = BoardView::parse(5, &[1, 1, 2, 1, 3, 1, 4, 1], 1, &[3, 3, 3, 2, 4, 2, 4, 3], &[], 50);
+        assert_eq!(prediction.move_probabilities(&view, 0, &[Action::Left, Action::Down]), vec![0.75, 0.25]);
+        assert_eq!(prediction.move_probabilities(&view, 1, &[Action::Up, Action::Down]), vec![0.5, 0.5]);
+    }
+
+    // 测试3：同一视野再次出现时，按记忆给出敌蛇上次的走法，即使它与所有候选模型都不同；
+    // 证实两次之前仍与均匀分布混合，之后成为已知走法
+    #[test]
+    fn test_memoized_reply() {
+        let mut models = OpponentModels::default();
+        let view = BoardView::parse(5, &[1, 1, 2, 1, 3, 1, 4, 1], 1, &[3, 3, 3, 2, 4, 2, 4, 3], &[5, 5], 50);
+        let first = models.predict(&view, &[0]);
+        assert_eq!(first.known_move(0), None);
+        // 敌蛇向左离开果子
+        models.observe(&[(0, (2, 3))]);
+        assert_eq!(models.memo().len(0), 1);
+        let again = models.predict(&view, &[0]);
+        assert_eq!(again.plausible_moves(0), Some(vec![Action::Left]));
+        assert_eq!(again.known_move(0), None);
+        let legal = [Action::Up, Action::Left, Action::Right];
+        assert!(again.move_probabilities(&view, 0, &legal)[0] > 0.1);
+        for _ in 0..2 {
+            models.observe(&[(0, (2, 3))]);
+            models.predict(&view, &[0]);
+        }
+        let known = models.predict(&view, &[0]);
+        assert_eq!(known.known_move(0), Some(Action::Left));
+        assert!(known.move_probabilities(&view, 0, &legal)[0] < 0.01);
+    }
+}
@@ -0,0 +1,140 @@
+//! 策略参数档案：每回合由棋盘边长、存活的蛇数与果子密度推导，
+//! 不依赖第一回合记下的游戏模式，蛇死亡后自动切换；只剩我方一条蛇时使用单独的档案。
+
+use crate::config::StrategyConfig;
+use crate::standing::{FOOD_RATE_5X5, FOOD_RATE_8X8};
+use crate::strategy::BoardView;
+
+/// 边长不超过该值的棋盘视为小棋盘（5x5）：空间紧张，领地更重要
+const SMALL_BOARD: i32 = 6;
+
+/// 局面类型
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProfileKind {
+    /// 只剩我方一条蛇：只管吃果子并活下去
+    Solo,
+    /// 只剩一条敌蛇
+    Duel,
+    /// 两条及以上敌蛇
+    Melee,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Profile {
+    pub kind: ProfileKind,
+    /// 棋盘中心，靠近中心的果子有额外奖励
+    pub center: (f64, f64),
+    pub survival_weight: f32,
+    pub aggression_weight: f32,
+    pub territory_weight: f32,
+    /// 同归于尽的奖励
+    pub kamikaze_bonus: f32,
+    /// 果子路线最多包含的果子数
+    pub route_len: usize,
+    /// 同时到达的果子按到达回合的扣分（比分不领先时 / 领先时）
+    pub tied_penalty: f32,
+    pub tied_penalty_leading: f32,
+    /// 每条蛇平均每回合吃到的果子数，供比分形势估计剩余回合的分差变化
+    pub food_rate: f32,
+}
+
+impl Profile {
+    /// n 为棋盘边长，alive_others 为存活的敌蛇数，foods 为果子数，权重取自 config
+    pub fn derive(n: i32, alive_others: usize, foods: usize, config: &StrategyConfig) -> Profile {
+        let small = n <= SMALL_BOARD;
+        let kind = match alive_others {
+            0 => ProfileKind::Solo,
+            1 => ProfileKind::Duel,
+            _ => ProfileKind::Melee,
+        };
+        // 每条存活的蛇平均分到至少两个果子时，值得规划连续吃多个果子的路线
+        let route_len = if kind == ProfileKind::Solo || (!small && foods >= 2 * (alive_others + 1)) {
+            config.route_len.max(1.0) as usize
+        } else {
+            1
+        };
+        let mut profile = Profile {
+            kind,
+            center: ((n + 1) as f64 / 2.0, (n + 1) as f64 / 2.0),
+            survival_weight: config.survival_weight,
+            aggression_weight: config.aggression_weight,
+            territory_weight: if small { config.territory_weight_small } else { config.territory_weight_large },
+            kamikaze_bonus: config.kamikaze_bonus,
+            route_len,
+            tied_penalty: 0.0,
+            tied_penalty_leading: config.tied_penalty_leading,
+            food_rate: if small { FOOD_RATE_5X5 } else { FOOD_RATE_8X8 },
+        };
+        match kind {
+            ProfileKind::Solo => {
+                // 没有对手：领地、进攻与争夺都没有意义
+                profile.aggression_weight = 0.0;
+                profile.territory_weight = 0.0;
+                profile.kamikaze_bonus = 0.0;
+                profile.tied_penalty_leading = 0.0;
+            }
+            ProfileKind::Duel => {}
+            ProfileKind::Melee => {
+                // 多条敌蛇时更看重生存，同归于尽只把名次让给旁观者，平局的果子也要避开
+                profile.survival_weight = config.survival_weight_melee;
+                profile.kamikaze_bonus = config.kamikaze_bonus_melee;
+                profile.tied_penalty = config.tied_penalty_melee;
+                profile.tied_penalty_leading = config.tied_penalty_melee;
+                // 恰好剩两条敌蛇时降低进攻权重，与原有的 snake_num == 2 规则一致
+                if alive_others == 2 {
+                    profile.aggression_weight = config.aggression_weight_two_left;
+                }
+            }
+        }
+        profile
+    }
+
+    pub fn from_view(view: &BoardView, config: &StrategyConfig) -> Profile {
+        Profile::derive(view.n, view.alive_others().count(), view.foods.len(), config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 测试1：三种预设模式分别得到对应的档案，中心点随棋盘边长变化
+    #[test]
+    fn test_presets() {
+        let config = StrategyConfig::default();
+        let duel = Profile::derive(5, 1, 5, &config);
+        assert_eq!(duel.kind, ProfileKind::Duel);
+        assert_eq!(duel.center, (3.0, 3.0));
+        assert_eq!(duel.route_len, 1);
+
+        let melee = Profile::derive(8, 3, 10, &config);
+        assert_eq!(melee.kind, ProfileKind::Melee);
+        assert_eq!(melee.center, (4.5, 4.5));
+        assert_eq!((melee.survival_weight, melee.route_len), (10.0, 3));
+
+        let custom = Profile::derive(12, 7, 20, &config);
+        assert_eq!(custom.center, (6.5, 6.5));
+        assert_eq!(custom.territory_weight, 5.0);
+        assert_eq!(custom.route_len, 3);
+    }
+
+    // 测试2：蛇死亡后档案随之切换
+    #[test]
+    fn test_switch_when_snakes_die() {
+        let config = StrategyConfig::default();
+        assert_eq!(Profile::derive(8, 1, 10, &config).kind, ProfileKind::Duel);
+        let solo = Profile::derive(8, 0, 10, &config);
+        assert_eq!(solo.kind, ProfileKind::Solo);
+        assert_eq!((solo.aggression_weight, solo.territory_weight), (0.0, 0.0));
+    }
+
+    // 测试3：4 蛇模式中剩两条敌蛇时使用较低的进攻权重，剩三条时恢复
+    #[test]
+    fn test_two_enemies_left() {
+        let config = StrategyConfig::default();
+        let two = Profile::derive(8, 2, 10, &config);
+        assert_eq!((two.kind, two.aggression_weight), (ProfileKind::Melee, 3.0));
+        assert_eq!(Profile::derive(8, 3, 10, &config).aggression_weight, 10.0);
+        assert_eq!(Profile::derive(8, 1, 10, &config).aggression_weight, 10.0);
+    }
+}
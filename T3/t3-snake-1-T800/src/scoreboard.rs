@@ -0,0 +1,149 @@
+//! 精确计分：比较相邻两回合的果子，找出消失的果子，记给此刻蛇头在该格上的蛇。
+//! 引擎先判定碰撞再吃果子，死亡的蛇不会吃掉果子，所以蛇头相撞时果子原样保留，不记分；
+//! 被吃掉的果子在空格中补充，不会落在吃掉它的蛇头上，所以消失的果子一定能找到存活的蛇头。
+//! 死亡的蛇得分保留，记分结果与引擎的 scores 数组一致。
+
+use board_engine::Pos;
+
+use crate::tracker::EnemyId;
+
+#[derive(Clone, Debug, Default)]
+pub struct Scoreboard {
+    // 上一回合的果子，第一次观察之前为 None
+    last_foods: Option<Vec<Pos>>,
+    mine: u32,
+    // 下标为敌蛇编号
+    enemies: Vec<u32>,
+    // 找不到吃掉它的蛇头的消失果子数，正常情况下始终为 0
+    unexplained: u32,
+}
+
+/// 上一回合存在、本回合消失的果子（按多重集合相减）
+fn vanished(last: &[Pos], current: &[Pos]) -> Vec<Pos> {
+    let mut remaining = current.to_vec();
+    last.iter()
+        .filter(|food| match remaining.iter().position(|f| f == *food) {
+            Some(i) => {
+                remaining.swap_remove(i);
+                false
+            }
+            None => true,
+        })
+        .copied()
+        .collect()
+}
+
+impl Scoreboard {
+    /// 每回合观察一次：my_head 为我方蛇头，enemies 为存活敌蛇的 (编号, 蛇头)，foods 为当前果子
+    pub fn update(&mut self, my_head: Pos, enemies: &[(EnemyId, Pos)], foods: &[Pos]) {
+        for &(id, _) in enemies {
+            if id >= self.enemies.len() {
+                self.enemies.resize(id + 1, 0);
+            }
+        }
+        if let Some(last) = &self.last_foods {
+            for food in vanished(last, foods) {
+                if my_head == food {
+                    self.mine += 1;
+                } else if let Some(&(id, _)) = enemies.iter().find(|&&(_, head)| head == food) {
+                    self.enemies[id] += 1;
+                } else {
+                    self.unexplained += 1;
+                }
+            }
+        }
+        self.last_foods = Some(foods.to_vec());
+    }
+
+    /// 我方得分
+    pub fn mine(&self) -> u32 {
+        self.mine
+    }
+
+    /// 编号为 id 的敌蛇的得分
+    pub fn enemy(&self, id: EnemyId) -> u32 {
+        self.enemies.get(id).copied().unwrap_or(0)
+    }
+
+    /// 出现过的全部敌蛇（包括已死亡的）的得分，下标为编号
+    pub fn enemies(&self) -> &[u32] {
+        &self.enemies
+    }
+
+    /// 无法归属的消失果子数，用于检查观察是否连续
+    pub fn unexplained(&self) -> u32 {
+        self.unexplained
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 测试1：消失的果子记给蛇头所在的蛇，第一次观察不记分
+    #[test]
+    fn test_credit_vanished_food() {
+        let mut board = Scoreboard::default();
+        board.update((1, 4), &[(0, (5, 2))], &[(1, 5), (5, 1), (3, 3)]);
+        assert_eq!((board.mine(), board.enemies()), (0, &[0][..]));
+        // 我方吃掉 (1,5)，敌蛇吃掉 (5,1)，补充的果子出现在别处
+        board.update((1, 5), &[(0, (5, 1))], &[(3, 3), (2, 2), (4, 4)]);
+        assert_eq!((board.mine(), board.enemy(0)), (1, 1));
+        assert_eq!(board.unexplained(), 0);
+    }
+
+    // 测试2：蛇头相撞时两条蛇都死亡，果子保留，不记分；死亡的蛇得分保留
+    #[test]
+    fn test_head_on_keeps_food() {
+        let mut board = Scoreboard::default();
+        board.update((1, 5), &[(0, (5, 1)), (1, (3, 1))], &[(5, 2), (8, 8)]);
+        // 敌蛇 0 吃掉 (5,2)，补充的果子出现在 (4,2)
+        board.update((1, 6), &[(0, (5, 2)), (1, (3, 2))], &[(8, 8), (4, 2)]);
+        assert_eq!(board.enemies(), &[1, 0]);
+        // 两条敌蛇同时进入 (4,2)：都从输入中消失，果子仍在
+        board.update((1, 7), &[], &[(8, 8), (4, 2)]);
+        assert_eq!(board.enemies(), &[1, 0]);
+        assert_eq!(board.unexplained(), 0);
+        assert_eq!(board.mine(), 0);
+    }
+
+    // 测试3：在原生对战场中与引擎的 scores 一致
+    #[test]
+    fn test_matches_engine_scores() {
+        use crate::arena::{run_match, ArenaConfig, Player};
+        use crate::SnakeSession;
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        // 这些种子下我方存活到最后，能看到全部记分；其中 33、50 有多条蛇存活
+        for seed in [1, 4, 9, 33, 50] {
+            let watched = Rc::new(RefCell::new(SnakeSession::new()));
+            let session = watched.clone();
+            let mut players: Vec<Player> = vec![Box::new(move |n, snake, snake_num, others, food_num, foods, round| {
+                session.borrow_mut().step(n, snake, snake_num, others, food_num, foods, round)
+            })];
+            for name in ["nearest-food", "space", "greedy"] {
+                let mut session = SnakeSession::with_strategy(name).unwrap();
+                players.push(Box::new(move |n, snake, snake_num, others, food_num, foods, round| {
+                    session.step(n, snake, snake_num, others, food_num, foods, round)
+                }));
+            }
+            let result = run_match(&ArenaConfig::four_snakes(), &mut players, seed);
+            assert!(result.alive[0], "seed {}: {:?}", seed, result.alive);
+            // 最后一回合的移动之后没有再调用，存活的蛇可能在最后一回合多吃一个果子，其余的蛇必须完全一致
+            let mut board = watched.borrow().scoreboard();
+            board.resize(result.scores.len(), 0);
+            for (i, &score) in result.scores.iter().enumerate() {
+                let missed = i64::from(score) - i64::from(board[i]);
+                assert!(
+                    missed == 0 || (result.alive[i] && missed == 1),
+                    "seed {} snake {}: {:?} vs {:?}",
+                    seed,
+                    i,
+                    board,
+                    result.scores
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,118 @@
+//! 多步搜索共用的局面模拟：所有蛇同时移动，碰撞规则与 board-engine 一致。
+
+use board_engine::{Action, Pos};
+
+use crate::strategy::{step, BoardView};
+
+/// 模拟用的局面：下标 0 为我方，其余为存活的敌蛇；被吃掉的果子不再补充（真实的补充位置取决于未知的种子）
+#[derive(Clone, Debug)]
+pub(crate) struct SimState {
+    pub n: i32,
+    pub bodies: Vec<Option<Vec<Pos>>>,
+    pub foods: Vec<Pos>,
+    /// 从模拟开始起每条蛇吃到的果子数
+    pub eaten: Vec<u32>,
+    /// 已模拟的回合数
+    pub depth: usize,
+}
+
+impl SimState {
+    pub fn from_view(view: &BoardView) -> SimState {
+        let bodies: Vec<Option<Vec<Pos>>> =
+            std::iter::once(&view.me).chain(view.alive_others()).map(|body| Some(body.clone())).collect();
+        let count = bodies.len();
+        SimState { n: view.n, bodies, foods: view.foods.clone(), eaten: vec![0; count], depth: 0 }
+    }
+
+    pub fn in_bounds(&self, (x, y): Pos) -> bool {
+        x >= 1 && y >= 1 && x <= self.n && y <= self.n
+    }
+
+    /// 每条存活蛇的合法方向：不撞墙、不撞除蛇尾以外的蛇身；无路可走时只保留向上（必死）
+    pub fn legal_moves(&self) -> Vec<Vec<Action>> {
+        let blocked: Vec<Pos> = self.bodies.iter().flatten().flat_map(|body| body[..body.len() - 1].iter().copied()).collect();
+        self.bodies
+            .iter()
+            .map(|body| match body {
+                None => Vec::new(),
+                Some(body) => {
+                    let moves: Vec<Action> = Action::ALL
+                        .into_iter()
+                        .filter(|&a| {
+                            let next = step(body[0], a);
+                            self.in_bounds(next) && !blocked.contains(&next)
+                        })
+                        .collect();
+                    if moves.is_empty() { vec![Action::Up] } else { moves }
+                }
+            })
+            .collect()
+    }
+
+    /// 所有存活的蛇同时移动，碰撞规则与 board-engine 一致
+    pub fn advance(&mut self, joint: &[Action]) {
+        let moved: Vec<Option<Vec<Pos>>> = self
+            .bodies
+            .iter()
+            .zip(joint)
+            .map(|(body, &action)| {
+                body.as_ref().map(|body| {
+                    let mut next = Vec::with_capacity(body.len());
+                    next.push(step(body[0], action));
+                    next.extend_from_slice(&body[..body.len() - 1]);
+                    next
+                })
+            })
+            .collect();
+        let dead: Vec<bool> = moved
+            .iter()
+            .enumerate()
+            .map(|(i, body)| match body {
+                None => false,
+                Some(body) => {
+                    let head = body[0];
+                    !self.in_bounds(head)
+                        || moved.iter().enumerate().any(|(j, other)| {
+                            other.iter().flatten().enumerate().any(|(k, &cell)| (i != j || k > 0) && cell == head)
+                        })
+                }
+            })
+            .collect();
+        for (i, body) in moved.into_iter().enumerate() {
+            self.bodies[i] = if dead[i] { None } else { body };
+            if let Some(body) = &self.bodies[i] {
+                if let Some(pos) = self.foods.iter().position(|&f| f == body[0]) {
+                    self.foods.swap_remove(pos);
+                    self.eaten[i] += 1;
+                }
+            }
+        }
+        self.depth += 1;
+    }
+
+    /// 从 start 出发、不经过任何蛇身的连通区域大小（蛇尾视为下一回合会腾出）
+    pub fn area_from(&self, start: Pos) -> usize {
+        let n = self.n;
+        let index = |(x, y): Pos| ((y - 1) * n + (x - 1)) as usize;
+        let mut visited = vec![false; (n * n) as usize];
+        for body in self.bodies.iter().flatten() {
+            for &cell in &body[..body.len() - 1] {
+                visited[index(cell)] = true;
+            }
+        }
+        visited[index(start)] = true;
+        let mut stack = vec![start];
+        let mut area = 0;
+        while let Some(cell) = stack.pop() {
+            area += 1;
+            for action in Action::ALL {
+                let next = step(cell, action);
+                if self.in_bounds(next) && !visited[index(next)] {
+                    visited[index(next)] = true;
+                    stack.push(next);
+                }
+            }
+        }
+        area
+    }
+}
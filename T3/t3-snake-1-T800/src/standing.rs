@@ -0,0 +1,90 @@
+//! 比分形势：课程按局内得分排名结算（4 蛇模式第 1~4 名得 3/2/1/0 积分，1v1 以得分高者胜），
+//! 所以决定冒险程度的是我方名次、与相邻名次的分差，以及剩余回合内分差还能变化多少。
+
+/// 每条蛇平均每回合吃到的果子数，用来估计剩余回合内还能追回的分数
+pub const FOOD_RATE_5X5: f32 = 0.12;
+pub const FOOD_RATE_8X8: f32 = 0.25;
+
+/// 冒险程度
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Stance {
+    /// 领先：避开争夺的果子，远离敌蛇蛇头
+    Cautious,
+    Neutral,
+    /// 落后且时间不多：按正常节奏追不回来，争夺果子、接受相撞风险
+    Reckless,
+}
+
+#[derive(Clone, Debug)]
+pub struct Standing {
+    pub my_score: f32,
+    /// 名次，0 为第一名；同分时按较好的名次计
+    pub rank: usize,
+    /// 领先下一名的分数，没有下一名时为 my_score
+    pub lead: f32,
+    /// 落后上一名的分数，第一名为 0
+    pub deficit: f32,
+    /// 剩余回合内一条蛇预计还能吃到的果子数
+    pub swing: f32,
+}
+
+impl Standing {
+    /// scores 为所有敌蛇（包括已死亡的，得分保留）的得分，remaining 为剩余回合数
+    pub fn new(my_score: f32, scores: &[f32], remaining: i32, food_rate: f32) -> Standing {
+        let rank = scores.iter().filter(|&&s| s > my_score).count();
+        let lead = scores.iter().filter(|&&s| s <= my_score).map(|&s| my_score - s).fold(my_score, f32::min);
+        let deficit = scores.iter().filter(|&&s| s > my_score).map(|&s| s - my_score).fold(f32::INFINITY, f32::min);
+        let deficit = if deficit.is_finite() { deficit } else { 0.0 };
+        Standing { my_score, rank, lead, deficit, swing: remaining.max(0) as f32 * food_rate }
+    }
+
+    pub fn stance(&self) -> Stance {
+        if self.rank == 0 && self.lead >= 1.0 {
+            Stance::Cautious
+        } else if self.deficit > 0.0 && self.deficit >= self.swing {
+            Stance::Reckless
+        } else {
+            Stance::Neutral
+        }
+    }
+
+    /// 与得分为 target 的敌蛇同归于尽是否划算：得分冻结后名次不会变差，
+    /// 即我方领先 target，且其余的蛇（bystanders，包括已死亡的）在剩余回合内既追不上我方、我方也追不上它们
+    pub fn trade_pays_off(&self, target: f32, bystanders: &[f32]) -> bool {
+        self.my_score > target && bystanders.iter().all(|&s| (self.my_score - s).abs() > self.swing)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 测试1：名次与分差；领先时谨慎，落后且来不及追时冒险
+    #[test]
+    fn test_stance() {
+        let standing = Standing::new(5.0, &[3.0, 4.0, 1.0], 40, FOOD_RATE_8X8);
+        assert_eq!((standing.rank, standing.lead, standing.deficit), (0, 1.0, 0.0));
+        assert_eq!(standing.stance(), Stance::Cautious);
+
+        let standing = Standing::new(2.0, &[5.0, 4.0, 1.0], 40, FOOD_RATE_8X8);
+        assert_eq!((standing.rank, standing.lead, standing.deficit), (2, 1.0, 2.0));
+        assert_eq!(standing.stance(), Stance::Neutral);
+        let standing = Standing::new(2.0, &[5.0, 4.0, 1.0], 4, FOOD_RATE_8X8);
+        assert_eq!(standing.stance(), Stance::Reckless);
+
+        // 同分不算领先
+        assert_eq!(Standing::new(3.0, &[3.0], 10, FOOD_RATE_5X5).stance(), Stance::Neutral);
+    }
+
+    // 测试2：1v1 中领先即可同归于尽；4 蛇模式中还要看其余的蛇能否追上
+    #[test]
+    fn test_trade_pays_off() {
+        let standing = Standing::new(4.0, &[3.0], 30, FOOD_RATE_5X5);
+        assert!(standing.trade_pays_off(3.0, &[]));
+        assert!(!standing.trade_pays_off(4.0, &[]));
+
+        let standing = Standing::new(6.0, &[3.0, 5.0, 0.0], 20, FOOD_RATE_8X8);
+        assert!(!standing.trade_pays_off(3.0, &[5.0]));
+        assert!(standing.trade_pays_off(3.0, &[0.0]));
+    }
+}
@@ -0,0 +1,380 @@
+//! 可插拔的决策策略：所有策略接收同一个解析后的棋盘视图，返回移动方向。
+
+use board_engine::{Action, Pos};
+use std::collections::VecDeque;
+
+use crate::config::StrategyConfig;
+use crate::deepening::{DeepeningConfig, IterativeDeepening, SurvivalLookahead};
+use crate::greedy::{GreedyStrategy, TunedStrategy};
+use crate::maxn::{MultiplayerSearch, SearchMode};
+use crate::mcts::MctsStrategy;
+use crate::opponent::OpponentModels;
+use crate::scoreboard::Scoreboard;
+use crate::tracker::EnemyTracker;
+
+/// 解析单条蛇的坐标（坐标从 1 开始），遇到 -1 则停止
+pub(crate) fn parse_snake_coords(snake: &[i32]) -> Vec<Pos> {
+    let mut coords = Vec::with_capacity(4);
+    for i in 0..snake.len() / 2 {
+        let x = snake[2 * i];
+        let y = snake[2 * i + 1];
+        if x >= 1 && y >= 1 {
+            coords.push((x, y));
+        } else {
+            break;
+        }
+    }
+    coords
+}
+
+/// 解析果子坐标（坐标从 1 开始）
+pub(crate) fn parse_food_coords(foods: &[i32]) -> Vec<Pos> {
+    let food_num = foods.len() / 2;
+    let mut coords = Vec::with_capacity(food_num);
+    for i in 0..food_num {
+        let x = foods[2 * i];
+        let y = foods[2 * i + 1];
+        if x >= 1 && y >= 1 {
+            coords.push((x, y));
+        }
+    }
+    coords
+}
+
+/// 一回合的棋盘视图，由 greedy_snake_step 的原始参数解析得到
+#[derive(Clone, Debug)]
+pub struct BoardView {
+    pub n: i32,
+    /// 我方蛇体，蛇头在前
+    pub me: Vec<Pos>,
+    /// 其他蛇的蛇体，已死亡的蛇为空
+    pub others: Vec<Vec<Pos>>,
+    pub foods: Vec<Pos>,
+    /// 引擎传入的其他蛇数量
+    pub snake_num: i32,
+    /// 剩余回合数
+    pub round: i32,
+}
+
+impl BoardView {
+    pub fn parse(n: i32, my_snake: &[i32], snake_num: i32, other_snakes: &[i32], foods: &[i32], round: i32) -> BoardView {
+        BoardView {
+            n,
+            me: parse_snake_coords(my_snake),
+            others: other_snakes.chunks(8).map(parse_snake_coords).collect(),
+            foods: parse_food_coords(foods),
+            snake_num,
+            round,
+        }
+    }
+
+    pub fn head(&self) -> Pos {
+        self.me[0]
+    }
+
+    /// 下标为 enemy 的敌蛇看到的棋盘：它成为 me，我方排在其余敌蛇之前
+    pub fn seen_by(&self, enemy: usize) -> BoardView {
+        let others: Vec<Vec<Pos>> = std::iter::once(&self.me)
+            .chain(self.others.iter().enumerate().filter(|&(i, _)| i != enemy).map(|(_, body)| body))
+            .cloned()
+            .collect();
+        BoardView {
+            n: self.n,
+            me: self.others[enemy].clone(),
+            snake_num: others.iter().filter(|body| !body.is_empty()).count() as i32,
+            others,
+            foods: self.foods.clone(),
+            round: self.round,
+        }
+    }
+
+    /// 仍存活的其他蛇
+    pub fn alive_others(&self) -> impl Iterator<Item = &Vec<Pos>> {
+        self.others.iter().filter(|body| !body.is_empty())
+    }
+
+    pub fn in_bounds(&self, (x, y): Pos) -> bool {
+        x >= 1 && y >= 1 && x <= self.n && y <= self.n
+    }
+
+    /// 将坐标转换为数组索引（调用前需保证在棋盘内）
+    pub fn index(&self, (x, y): Pos) -> usize {
+        ((y - 1) * self.n + (x - 1)) as usize
+    }
+
+    /// 下一回合仍被占用的格子：蛇不会变长，每条蛇的蛇尾都会腾出
+    pub fn blocked_next_turn(&self) -> Vec<bool> {
+        let mut blocked = vec![false; (self.n * self.n) as usize];
+        for body in std::iter::once(&self.me).chain(self.alive_others()) {
+            for &cell in &body[..body.len() - 1] {
+                if self.in_bounds(cell) {
+                    blocked[self.index(cell)] = true;
+                }
+            }
+        }
+        blocked
+    }
+
+    /// 不会撞墙或撞上蛇身的方向（不考虑蛇头相撞）
+    pub fn safe_moves(&self) -> Vec<Action> {
+        let blocked = self.blocked_next_turn();
+        let head = self.head();
+        Action::ALL
+            .into_iter()
+            .filter(|action| {
+                let next = step(head, *action);
+                self.in_bounds(next) && !blocked[self.index(next)]
+            })
+            .collect()
+    }
+
+    /// 从 start 出发、避开 blocked 的连通区域大小
+    pub fn flood_fill(&self, start: Pos, blocked: &[bool]) -> usize {
+        if !self.in_bounds(start) || blocked[self.index(start)] {
+            return 0;
+        }
+        let mut visited = blocked.to_vec();
+        visited[self.index(start)] = true;
+        let mut stack = vec![start];
+        let mut area = 0;
+        while let Some(cell) = stack.pop() {
+            area += 1;
+            for action in Action::ALL {
+                let next = step(cell, action);
+                if self.in_bounds(next) && !visited[self.index(next)] {
+                    visited[self.index(next)] = true;
+                    stack.push(next);
+                }
+            }
+        }
+        area
+    }
+}
+
+/// 沿 action 方向移动一格
+pub fn step((x, y): Pos, action: Action) -> Pos {
+    let (dx, dy) = action.delta();
+    (x + dx, y + dy)
+}
+
+/// 决策策略：每回合由会话调用一次 choose，新的一局开始时调用 reset
+pub trait SnakeStrategy {
+    /// 策略名称，与 strategy_by_name 的参数一致
+    fn name(&self) -> &'static str;
+
+    fn choose(&mut self, view: &BoardView) -> Action;
+
+    /// 清空跨回合的记忆，无状态的策略无需实现
+    fn reset(&mut self) {}
+
+    /// 可调参数，不使用 StrategyConfig 的策略返回 None
+    fn config(&self) -> Option<&StrategyConfig> {
+        None
+    }
+
+    fn config_mut(&mut self) -> Option<&mut StrategyConfig> {
+        None
+    }
+
+    /// 敌蛇身份跟踪，不跟踪敌蛇的策略返回 None
+    fn tracker(&self) -> Option<&EnemyTracker> {
+        None
+    }
+
+    /// 按果子消失记录的得分，不记分的策略返回 None
+    fn scoreboard(&self) -> Option<&Scoreboard> {
+        None
+    }
+
+    /// 在线学习的敌蛇行为模型，不学习的策略返回 None
+    fn opponents(&self) -> Option<&OpponentModels> {
+        None
+    }
+}
+
+/// 全部内置策略的名称
+pub const STRATEGY_NAMES: [&str; 9] =
+    ["greedy", "tuned", "random", "nearest-food", "space", "mcts", "deepening", "maxn", "paranoid"];
+
+/// 按名称创建内置策略
+pub fn strategy_by_name(name: &str) -> Option<Box<dyn SnakeStrategy>> {
+    match name {
+        "greedy" => Some(Box::new(GreedyStrategy::default())),
+        "tuned" => Some(Box::new(TunedStrategy::default())),
+        "random" => Some(Box::new(RandomSafe::default())),
+        "nearest-food" => Some(Box::new(NearestFood)),
+        "space" => Some(Box::new(SpaceMaximizer)),
+        "mcts" => Some(Box::new(MctsStrategy::default())),
+        "deepening" => Some(Box::new(IterativeDeepening::new(DeepeningConfig::default(), SurvivalLookahead::default()))),
+        "maxn" => Some(Box::new(IterativeDeepening::new(DeepeningConfig::default(), MultiplayerSearch::new(SearchMode::MaxN)))),
+        "paranoid" => Some(Box::new(IterativeDeepening::new(
+            DeepeningConfig::default(),
+            MultiplayerSearch::new(SearchMode::Paranoid),
+        ))),
+        _ => None,
+    }
+}
+
+/// xorshift64 伪随机数，不依赖外部随机源，保证同一局面可复现
+#[derive(Clone, Debug)]
+pub struct XorShift(u64);
+
+impl Default for XorShift {
+    fn default() -> Self {
+        XorShift(0x9E3779B97F4A7C15)
+    }
+}
+
+impl XorShift {
+    /// 以 seed 为种子，种子为 0 时使用默认种子
+    pub fn new(seed: u64) -> XorShift {
+        if seed == 0 { XorShift::default() } else { XorShift(seed) }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// [0, bound) 内的随机整数
+    pub fn below(&mut self, bound: usize) -> usize {
+        (self.next_u64() % bound as u64) as usize
+    }
+
+    /// [0, 1) 内的随机小数
+    pub fn unit(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// 随机选择一个安全方向；没有安全方向时向上
+#[derive(Default)]
+pub struct RandomSafe {
+    rng: XorShift,
+}
+
+impl SnakeStrategy for RandomSafe {
+    fn name(&self) -> &'static str {
+        "random"
+    }
+
+    fn choose(&mut self, view: &BoardView) -> Action {
+        let moves = view.safe_moves();
+        if moves.is_empty() {
+            return Action::Up;
+        }
+        moves[self.rng.below(moves.len())]
+    }
+
+    fn reset(&mut self) {
+        *self = RandomSafe::default();
+    }
+}
+
+/// T1 的寻路方式：BFS 找到最近的果子，返回路径的第一步；找不到时选第一个安全方向
+pub struct NearestFood;
+
+impl SnakeStrategy for NearestFood {
+    fn name(&self) -> &'static str {
+        "nearest-food"
+    }
+
+    fn choose(&mut self, view: &BoardView) -> Action {
+        let mut visited = view.blocked_next_turn();
+        let mut queue = VecDeque::new();
+        // 队列中记录 (坐标, 首步方向)，按上、左、下、右的顺序扩展
+        for action in Action::ALL {
+            let next = step(view.head(), action);
+            if view.in_bounds(next) && !visited[view.index(next)] {
+                visited[view.index(next)] = true;
+                queue.push_back((next, action));
+            }
+        }
+        while let Some((cell, first)) = queue.pop_front() {
+            if view.foods.contains(&cell) {
+                return first;
+            }
+            for action in Action::ALL {
+                let next = step(cell, action);
+                if view.in_bounds(next) && !visited[view.index(next)] {
+                    visited[view.index(next)] = true;
+                    queue.push_back((next, first));
+                }
+            }
+        }
+        view.safe_moves().first().copied().unwrap_or(Action::Up)
+    }
+}
+
+/// 只追求空间：选择移动后可活动区域最大的方向
+pub struct SpaceMaximizer;
+
+impl SnakeStrategy for SpaceMaximizer {
+    fn name(&self) -> &'static str {
+        "space"
+    }
+
+    fn choose(&mut self, view: &BoardView) -> Action {
+        let blocked = view.blocked_next_turn();
+        let mut best = (Action::Up, 0);
+        for action in view.safe_moves() {
+            let next = step(view.head(), action);
+            // 移动后蛇头所在格也被占用，从蛇头的邻格开始计算
+            let mut after = blocked.clone();
+            after[view.index(next)] = true;
+            let area = 1 + Action::ALL
+                .into_iter()
+                .map(|a| view.flood_fill(step(next, a), &after))
+                .max()
+                .unwrap_or(0);
+            if area > best.1 {
+                best = (action, area);
+            }
+        }
+        best.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn view(me: &[i32], others: &[i32], foods: &[i32]) -> BoardView {
+        BoardView::parse(5, me, (others.len() / 8) as i32, others, foods, 50)
+    }
+
+    // 测试1：蛇尾会腾出，可以安全地追随自己的尾巴
+    #[test]
+    fn test_safe_moves_follow_tail() {
+        let v = view(&[2, 2, 2, 1, 1, 1, 1, 2], &[], &[]);
+        assert_eq!(v.safe_moves(), vec![Action::Up, Action::Left, Action::Right]);
+    }
+
+    // 测试2：nearest-food 沿最短路径走向最近的果子
+    #[test]
+    fn test_nearest_food() {
+        let v = view(&[1, 4, 1, 3, 1, 2, 1, 1], &[5, 2, 5, 3, 5, 4, 5, 5], &[4, 4, 1, 5]);
+        assert_eq!(NearestFood.choose(&v), Action::Up);
+        let v = view(&[1, 4, 1, 3, 1, 2, 1, 1], &[5, 2, 5, 3, 5, 4, 5, 5], &[3, 4]);
+        assert_eq!(NearestFood.choose(&v), Action::Right);
+    }
+
+    // 测试3：space 避开死角
+    #[test]
+    fn test_space_avoids_pocket() {
+        // 向上进入 (1,5) 的角落只剩 1 格，向右则是开阔区域
+        let v = view(&[1, 4, 1, 3, 1, 2, 1, 1], &[2, 5, 3, 5, 3, 4, 3, 3], &[]);
+        assert_eq!(SpaceMaximizer.choose(&v), Action::Right);
+    }
+
+    // 测试4：按名称创建策略
+    #[test]
+    fn test_strategy_by_name() {
+        for name in STRATEGY_NAMES {
+            assert_eq!(strategy_by_name(name).unwrap().name(), name);
+        }
+        assert!(strategy_by_name("unknown").is_none());
+    }
+}
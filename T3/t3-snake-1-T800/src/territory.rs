@@ -0,0 +1,130 @@
+//! Voronoi 领地：所有蛇头同时做多源广度优先搜索，每个空格子归最先到达的蛇，
+//! 同时到达的格子记为争夺格，不再向外扩展。格子能否进入按 Occupancy 的腾出时间判断。
+
+use board_engine::{Action, Pos};
+
+use crate::occupancy::Occupancy;
+use crate::strategy::step;
+
+/// 格子的归属
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Owner {
+    /// 没有任何蛇头能到达
+    Unreached,
+    /// 归 sources 中第 i 条蛇
+    Snake(usize),
+    /// 多条蛇同时到达
+    Contested,
+}
+
+/// 各蛇的领地统计，下标与 voronoi 的 sources 一一对应
+#[derive(Clone, Debug)]
+pub struct Territory {
+    pub owner: Vec<Owner>,
+    /// 每条蛇领地内的格子数（包括蛇头所在格）
+    pub cells: Vec<usize>,
+    /// 每条蛇领地内的果子数
+    pub foods: Vec<usize>,
+    /// 争夺格的数量
+    pub contested: usize,
+}
+
+/// sources[i] = (蛇头, 蛇头所在的回合)，回合小的先出发；已死亡的蛇传 None。
+/// 同一回合到达同一格子的两条蛇平分该格，记为争夺。
+pub fn voronoi(occupancy: &Occupancy, sources: &[Option<(Pos, u32)>], foods: &[Pos]) -> Territory {
+    let n = occupancy.n();
+    let index = |(x, y): Pos| ((y - 1) * n + (x - 1)) as usize;
+    let mut owner = vec![Owner::Unreached; (n * n) as usize];
+    let mut arrival = vec![u32::MAX; (n * n) as usize];
+    let mut frontier: Vec<(Pos, usize)> = Vec::new();
+    let Some(mut t) = sources.iter().flatten().map(|&(_, turn)| turn).min() else {
+        return Territory { owner, cells: vec![0; sources.len()], foods: vec![0; sources.len()], contested: 0 };
+    };
+    loop {
+        // 本回合出发的蛇头加入前沿
+        for (i, &source) in sources.iter().enumerate() {
+            let Some((head, turn)) = source else { continue };
+            if turn == t && occupancy.in_bounds(head) {
+                let idx = index(head);
+                owner[idx] = Owner::Snake(i);
+                arrival[idx] = t;
+                frontier.push((head, i));
+            }
+        }
+        let pending = sources.iter().flatten().any(|&(_, turn)| turn > t);
+        if frontier.is_empty() && !pending {
+            break;
+        }
+        t += 1;
+        let mut next_frontier: Vec<(Pos, usize)> = Vec::new();
+        for &(cell, snake) in &frontier {
+            if owner[index(cell)] == Owner::Contested {
+                continue;
+            }
+            for action in Action::ALL {
+                let next = step(cell, action);
+                if !occupancy.can_enter(next, t) {
+                    continue;
+                }
+                let idx = index(next);
+                if arrival[idx] == u32::MAX {
+                    arrival[idx] = t;
+                    owner[idx] = Owner::Snake(snake);
+                    next_frontier.push((next, snake));
+                } else if arrival[idx] == t && owner[idx] != Owner::Snake(snake) {
+                    owner[idx] = Owner::Contested;
+                }
+            }
+        }
+        frontier = next_frontier;
+    }
+
+    let mut cells = vec![0; sources.len()];
+    let mut contested = 0;
+    for &o in &owner {
+        match o {
+            Owner::Snake(i) => cells[i] += 1,
+            Owner::Contested => contested += 1,
+            Owner::Unreached => {}
+        }
+    }
+    let mut food_counts = vec![0; sources.len()];
+    for &food in foods.iter().filter(|&&f| occupancy.in_bounds(f)) {
+        if let Owner::Snake(i) = owner[index(food)] {
+            food_counts[i] += 1;
+        }
+    }
+    Territory { owner, cells, foods: food_counts, contested }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 测试1：两个蛇头在一行上相对，中间的格子是争夺格，其余按距离划分
+    #[test]
+    fn test_split_and_contested() {
+        let occupancy = Occupancy::new(5);
+        let sources = [Some(((1, 3), 0)), Some(((5, 3), 0))];
+        let territory = voronoi(&occupancy, &sources, &[(1, 1), (2, 5), (5, 5)]);
+        // x=3 一整列都是争夺格，左右各 10 格
+        assert_eq!(territory.contested, 5);
+        assert_eq!(territory.cells, vec![10, 10]);
+        assert_eq!(territory.foods, vec![2, 1]);
+        assert_eq!(territory.owner[12], Owner::Contested);
+    }
+
+    // 测试2：晚出发一回合的蛇头少占格子，蛇身挡住的区域要等腾出后才能进入
+    #[test]
+    fn test_late_start_and_bodies() {
+        let occupancy = Occupancy::new(5);
+        let territory = voronoi(&occupancy, &[Some(((1, 3), 1)), Some(((5, 3), 0))], &[]);
+        assert!(territory.cells[0] < territory.cells[1]);
+
+        // 一条竖直的长蛇把棋盘隔开，左边的蛇头只能占左边一列
+        let wall = vec![(2, 5), (2, 4), (2, 3), (2, 2), (2, 1), (3, 1), (4, 1), (5, 1), (5, 2), (5, 3)];
+        let occupancy = Occupancy::from_bodies(5, [&wall]);
+        let territory = voronoi(&occupancy, &[Some(((1, 1), 0)), Some(((4, 4), 0))], &[]);
+        assert_eq!(territory.cells[0], 5);
+    }
+}
@@ -0,0 +1,188 @@
+//! 敌蛇身份跟踪：引擎只传入存活的敌蛇，且每回合的顺序可能变化，需要自己为每条敌蛇维持固定的编号。
+//! 按移动规则匹配：新蛇体等于旧蛇体整体后移一格，新蛇头与旧蛇头相邻。
+//! 同一条新蛇体的第二节就是旧蛇头，所以符合移动规则的配对是唯一的；
+//! 不符合时（例如漏掉了一回合）按重叠格子数退而求其次，所有配对一起求总分最大的分配。
+//! 上一回合存活、本回合没有配对的敌蛇记为死亡。
+
+use board_engine::Pos;
+
+/// 敌蛇编号，整局不变，按第一次出现的顺序从 0 开始
+pub type EnemyId = usize;
+
+// 符合移动规则的配对得分，高于任何重叠格子数
+const MOTION_MATCH: u32 = 100;
+
+// 不符合移动规则时，至少重叠这么多格子才认为是同一条蛇
+const MIN_OVERLAP: u32 = 2;
+
+/// 检测到的敌蛇死亡
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DeathEvent {
+    pub id: EnemyId,
+    /// 发现它消失时的剩余回合数，死亡发生在上一回合的移动中
+    pub round: i32,
+    /// 最后一次看到的蛇体
+    pub body: Vec<Pos>,
+}
+
+#[derive(Clone, Debug)]
+struct Track {
+    id: EnemyId,
+    body: Vec<Pos>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct EnemyTracker {
+    // 上一次更新后仍存活的敌蛇
+    tracks: Vec<Track>,
+    // 上一次更新的输入中每条敌蛇的编号
+    ids: Vec<EnemyId>,
+    deaths: Vec<DeathEvent>,
+    next_id: EnemyId,
+}
+
+/// body 能否由 prev 移动一步得到
+pub fn follows(prev: &[Pos], body: &[Pos]) -> bool {
+    let (Some(&(px, py)), Some(&(x, y))) = (prev.first(), body.first()) else {
+        return false;
+    };
+    (px - x).abs() + (py - y).abs() == 1 && body.len() <= prev.len() + 1 && body[1..] == prev[..body.len() - 1]
+}
+
+/// 同一条蛇的可能性得分，0 表示不可能
+fn match_score(prev: &[Pos], body: &[Pos]) -> u32 {
+    if follows(prev, body) {
+        return MOTION_MATCH;
+    }
+    let overlap = body.iter().filter(|cell| prev.contains(cell)).count() as u32;
+    if overlap >= MIN_OVERLAP { overlap } else { 0 }
+}
+
+/// 总分最大的分配：第 i 条新蛇体配给第 assignment[i] 条旧记录，None 表示没有配对。
+/// 按旧记录的占用状态做状态压缩 DP，敌蛇不超过 7 条时状态数很小
+fn assign(scores: &[Vec<u32>], tracks: usize) -> Vec<Option<usize>> {
+    let states = 1usize << tracks;
+    // best[i][mask]：从第 i 条新蛇体开始、已占用 mask 时剩余部分的最大得分
+    let mut best = vec![vec![0u32; states]; scores.len() + 1];
+    for i in (0..scores.len()).rev() {
+        for mask in 0..states {
+            let mut value = best[i + 1][mask];
+            for (j, &score) in scores[i].iter().enumerate() {
+                if score > 0 && mask & (1 << j) == 0 {
+                    value = value.max(score + best[i + 1][mask | (1 << j)]);
+                }
+            }
+            best[i][mask] = value;
+        }
+    }
+    let mut mask = 0;
+    let mut assignment = Vec::with_capacity(scores.len());
+    for (i, row) in scores.iter().enumerate() {
+        let choice = row
+            .iter()
+            .enumerate()
+            .find(|&(j, &score)| score > 0 && mask & (1 << j) == 0 && score + best[i + 1][mask | (1 << j)] == best[i][mask])
+            .map(|(j, _)| j);
+        if let Some(j) = choice {
+            mask |= 1 << j;
+        }
+        assignment.push(choice);
+    }
+    assignment
+}
+
+impl EnemyTracker {
+    /// 用本回合存活的敌蛇蛇体更新跟踪，返回与 bodies 下标一致的编号；round 为剩余回合数
+    pub fn update(&mut self, bodies: &[Vec<Pos>], round: i32) -> &[EnemyId] {
+        let scores: Vec<Vec<u32>> =
+            bodies.iter().map(|body| self.tracks.iter().map(|track| match_score(&track.body, body)).collect()).collect();
+        let assignment = assign(&scores, self.tracks.len());
+
+        let mut matched = vec![false; self.tracks.len()];
+        let mut ids = Vec::with_capacity(bodies.len());
+        for choice in &assignment {
+            match *choice {
+                Some(j) => {
+                    matched[j] = true;
+                    ids.push(self.tracks[j].id);
+                }
+                None => {
+                    ids.push(self.next_id);
+                    self.next_id += 1;
+                }
+            }
+        }
+        for (track, _) in self.tracks.iter().zip(&matched).filter(|&(_, &m)| !m) {
+            self.deaths.push(DeathEvent { id: track.id, round, body: track.body.clone() });
+        }
+        self.tracks = ids.iter().zip(bodies).map(|(&id, body)| Track { id, body: body.clone() }).collect();
+        self.ids = ids;
+        &self.ids
+    }
+
+    /// 上一次更新的输入中每条敌蛇的编号
+    pub fn ids(&self) -> &[EnemyId] {
+        &self.ids
+    }
+
+    /// 本局检测到的全部死亡，按发现的先后排列
+    pub fn deaths(&self) -> &[DeathEvent] {
+        &self.deaths
+    }
+
+    /// 编号为 id 的敌蛇上一次更新时的蛇体，已死亡或不存在时返回 None
+    pub fn body(&self, id: EnemyId) -> Option<&[Pos]> {
+        self.tracks.iter().find(|track| track.id == id).map(|track| track.body.as_slice())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 测试1：两条蛇并排前进且输入顺序交换时，编号跟随移动而不是下标
+    #[test]
+    fn test_side_by_side_swap() {
+        let mut tracker = EnemyTracker::default();
+        let a = vec![(4, 1), (3, 1), (2, 1), (1, 1)];
+        let b = vec![(4, 2), (3, 2), (2, 2), (1, 2)];
+        assert_eq!(tracker.update(&[a, b], 50), &[0, 1]);
+        // 两条蛇并排向右移动一格，且输入顺序交换
+        let a = vec![(5, 1), (4, 1), (3, 1), (2, 1)];
+        let b = vec![(5, 2), (4, 2), (3, 2), (2, 2)];
+        assert_eq!(tracker.update(&[b.clone(), a], 49), &[1, 0]);
+        assert_eq!(tracker.body(1), Some(b.as_slice()));
+        assert!(tracker.deaths().is_empty());
+    }
+
+    // 测试2：消失的蛇记为死亡，其余蛇的编号不变
+    #[test]
+    fn test_death_events() {
+        let mut tracker = EnemyTracker::default();
+        let bodies = [
+            vec![(1, 5), (1, 6), (1, 7), (1, 8)],
+            vec![(8, 4), (8, 3), (8, 2), (8, 1)],
+            vec![(5, 8), (6, 8), (7, 8), (8, 8)],
+        ];
+        tracker.update(&bodies, 100);
+        let survivor = vec![(5, 7), (5, 8), (6, 8), (7, 8)];
+        assert_eq!(tracker.update(&[survivor], 99), &[2]);
+        let ids: Vec<EnemyId> = tracker.deaths().iter().map(|death| death.id).collect();
+        assert_eq!(ids, vec![0, 1]);
+        assert_eq!(tracker.deaths()[1].round, 99);
+        assert_eq!(tracker.body(0), None);
+    }
+
+    // 测试3：漏掉一回合时按重叠格子数配对；完全对不上的蛇体视为新的蛇
+    #[test]
+    fn test_overlap_fallback() {
+        let mut tracker = EnemyTracker::default();
+        tracker.update(&[vec![(3, 3), (3, 2), (3, 1), (2, 1)]], 50);
+        // 移动了两格
+        let moved = vec![(3, 5), (3, 4), (3, 3), (3, 2)];
+        assert!(!follows(&[(3, 3), (3, 2), (3, 1), (2, 1)], &moved));
+        assert_eq!(tracker.update(&[moved], 48), &[0]);
+        assert_eq!(tracker.update(&[vec![(1, 1), (1, 2), (1, 3), (1, 4)]], 47), &[1]);
+        assert_eq!(tracker.deaths().len(), 1);
+    }
+}
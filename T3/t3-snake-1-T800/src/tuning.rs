@@ -0,0 +1,313 @@
+//! 自我对弈调参：用交叉熵方法（CEM）优化 StrategyConfig 中的参数。
+//! 每一代按高斯分布采样一批参数，在原生对战场中与固定的参考策略进行带种子的 1v1 与 4 蛇对局，
+//! 取胜率最高的一部分参数重新估计分布的均值与标准差。
+//! 同一代的所有候选使用相同的种子，减少比较时的随机误差。
+
+use std::thread;
+
+use crate::arena::{run_match, ArenaConfig, MatchResult, Player};
+use crate::config::StrategyConfig;
+use crate::greedy::GreedyStrategy;
+use crate::strategy::{strategy_by_name, XorShift};
+use crate::SnakeSession;
+
+/// 优化过程的参数
+#[derive(Clone, Debug)]
+pub struct TuningConfig {
+    /// 参与优化的参数名，其余参数保持初始值
+    pub keys: Vec<String>,
+    /// 每一代采样的候选数（包括当前均值本身）
+    pub population: usize,
+    /// 用于更新分布的最优候选数
+    pub elite: usize,
+    /// 每个候选在每种模式下进行的对局数
+    pub games: usize,
+    /// 参考策略的名称，对局中按座位依次循环使用
+    pub references: Vec<String>,
+    /// 初始标准差占参数绝对值的比例
+    pub initial_spread: f32,
+    /// 新分布与旧分布的混合比例，1 表示完全采用精英的估计
+    pub smoothing: f32,
+    /// 标准差的下限，避免过早收敛
+    pub min_std: f32,
+    /// 采样与对局种子的起点
+    pub seed: u64,
+}
+
+impl Default for TuningConfig {
+    fn default() -> Self {
+        TuningConfig {
+            keys: [
+                "score_weight",
+                "survival_weight",
+                "survival_weight_melee",
+                "aggression_weight",
+                "aggression_weight_two_left",
+                "kamikaze_bonus",
+                "kamikaze_bonus_melee",
+                "head_on_cost",
+                "territory_weight_small",
+                "territory_weight_large",
+                "tied_penalty_melee",
+                "pocket_cost",
+                "pocket_size",
+            ]
+            .iter()
+            .map(|key| key.to_string())
+            .collect(),
+            population: 16,
+            elite: 4,
+            games: 20,
+            references: vec!["greedy".to_string(), "nearest-food".to_string(), "space".to_string()],
+            initial_spread: 0.3,
+            smoothing: 0.7,
+            min_std: 0.05,
+            seed: 1,
+        }
+    }
+}
+
+/// 候选参数的对局成绩，均为 [0, 1] 内的比例
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Fitness {
+    /// 1v1 的胜率，平局计半场
+    pub duel: f64,
+    /// 4 蛇模式中击败的对手比例，同分计半个，对应课程的 3/2/1/0 积分
+    pub melee: f64,
+}
+
+impl Fitness {
+    pub fn total(&self) -> f64 {
+        (self.duel + self.melee) / 2.0
+    }
+}
+
+/// 一代的统计
+#[derive(Clone, Debug)]
+pub struct Generation {
+    pub index: usize,
+    /// 本代开始时分布均值的成绩
+    pub center: Fitness,
+    /// 本代最优候选的成绩
+    pub best: Fitness,
+    /// 全部候选的平均总成绩
+    pub average: f64,
+    /// 更新后的分布均值
+    pub mean: StrategyConfig,
+}
+
+/// 使用给定参数的加权启发式策略作为参赛者
+fn candidate_player(config: &StrategyConfig) -> Player {
+    let mut session = SnakeSession::with_boxed_strategy(Box::new(GreedyStrategy::with_config(config.clone())));
+    Box::new(move |n, snake, snake_num, others, food_num, foods, round| {
+        session.step(n, snake, snake_num, others, food_num, foods, round)
+    })
+}
+
+fn reference_player(name: &str) -> Player {
+    let mut session = SnakeSession::with_boxed_strategy(strategy_by_name(name).expect("unknown reference strategy"));
+    Box::new(move |n, snake, snake_num, others, food_num, foods, round| {
+        session.step(n, snake, snake_num, others, food_num, foods, round)
+    })
+}
+
+/// 候选坐在 seat 号座位，其余座位依次使用参考策略，返回对局结果
+fn play(arena: &ArenaConfig, config: &StrategyConfig, references: &[String], seat: usize, seed: u64) -> MatchResult {
+    let seats = arena.initial_positions.len();
+    let mut players: Vec<Player> = (0..seats)
+        .map(|i| {
+            if i == seat {
+                candidate_player(config)
+            } else {
+                reference_player(&references[(i + seats - seat - 1) % references.len()])
+            }
+        })
+        .collect();
+    run_match(arena, &mut players, seed)
+}
+
+/// 座位 seat 的蛇击败的对手比例，同分计半个
+fn beaten_share(result: &MatchResult, seat: usize) -> f64 {
+    let mine = result.scores[seat];
+    let others = result.scores.len() - 1;
+    let beaten: f64 = (0..result.scores.len())
+        .filter(|&i| i != seat)
+        .map(|i| match mine.cmp(&result.scores[i]) {
+            std::cmp::Ordering::Greater => 1.0,
+            std::cmp::Ordering::Equal => 0.5,
+            std::cmp::Ordering::Less => 0.0,
+        })
+        .sum();
+    beaten / others as f64
+}
+
+/// 在 seeds 上分别进行 1v1 与 4 蛇对局，候选的座位随种子轮换
+pub fn evaluate(config: &StrategyConfig, references: &[String], seeds: &[u64]) -> Fitness {
+    let modes = [ArenaConfig::one_vs_one(), ArenaConfig::four_snakes()];
+    let [duel, melee] = modes.map(|arena| {
+        let seats = arena.initial_positions.len();
+        let total: f64 = seeds
+            .iter()
+            .map(|&seed| {
+                let seat = (seed % seats as u64) as usize;
+                beaten_share(&play(&arena, config, references, seat, seed), seat)
+            })
+            .sum();
+        total / seeds.len().max(1) as f64
+    });
+    Fitness { duel, melee }
+}
+
+/// 标准正态分布的随机数（Box-Muller 变换）
+fn gaussian(rng: &mut XorShift) -> f32 {
+    let u = 1.0 - rng.unit();
+    let v = rng.unit();
+    ((-2.0 * u.ln()).sqrt() * (2.0 * std::f64::consts::PI * v).cos()) as f32
+}
+
+/// 交叉熵方法的状态：每个待优化参数一个独立的高斯分布
+pub struct CrossEntropy {
+    tuning: TuningConfig,
+    base: StrategyConfig,
+    mean: Vec<f32>,
+    std: Vec<f32>,
+    // 参数的取值符号：权重与奖励不应翻转符号
+    sign: Vec<f32>,
+    rng: XorShift,
+    generation: usize,
+}
+
+impl CrossEntropy {
+    /// 从 base 出发优化 tuning.keys 中的参数，参数名不存在时返回 None
+    pub fn new(tuning: TuningConfig, base: StrategyConfig) -> Option<CrossEntropy> {
+        let values = base.to_vec();
+        let mean = tuning
+            .keys
+            .iter()
+            .map(|key| StrategyConfig::KEYS.iter().position(|k| k == key).map(|i| values[i]))
+            .collect::<Option<Vec<f32>>>()?;
+        let std = mean.iter().map(|v| (v.abs() * tuning.initial_spread).max(tuning.min_std)).collect();
+        let sign = mean.iter().map(|&v| if v < 0.0 { -1.0 } else { 1.0 }).collect();
+        let rng = XorShift::new(tuning.seed);
+        Some(CrossEntropy { tuning, base, mean, std, sign, rng, generation: 0 })
+    }
+
+    fn config_from(&self, values: &[f32]) -> StrategyConfig {
+        let mut config = self.base.clone();
+        for (key, &value) in self.tuning.keys.iter().zip(values) {
+            config.set(key, value);
+        }
+        config
+    }
+
+    /// 当前分布均值对应的参数
+    pub fn mean(&self) -> StrategyConfig {
+        self.config_from(&self.mean)
+    }
+
+    fn sample(&mut self) -> Vec<f32> {
+        (0..self.mean.len())
+            .map(|i| {
+                let value = self.mean[i] + self.std[i] * gaussian(&mut self.rng);
+                // 保持符号不变
+                if value * self.sign[i] < 0.0 { 0.0 } else { value }
+            })
+            .collect()
+    }
+
+    /// 第 generation 代使用的对局种子，每一代不同
+    pub fn seeds(&self) -> Vec<u64> {
+        let start = self.tuning.seed.wrapping_add((self.generation * self.tuning.games) as u64);
+        (start..start + self.tuning.games as u64).collect()
+    }
+
+    /// 进行一代：候选中第一个为当前均值，fitness 在多个线程中并行调用
+    pub fn step<F>(&mut self, fitness: F) -> Generation
+    where
+        F: Fn(&StrategyConfig) -> Fitness + Sync,
+    {
+        let mut samples = vec![self.mean.clone()];
+        while samples.len() < self.tuning.population.max(2) {
+            samples.push(self.sample());
+        }
+        let configs: Vec<StrategyConfig> = samples.iter().map(|values| self.config_from(values)).collect();
+        let scores = evaluate_all(&configs, &fitness);
+
+        let mut order: Vec<usize> = (0..samples.len()).collect();
+        order.sort_by(|&a, &b| scores[b].total().total_cmp(&scores[a].total()));
+        let elite = &order[..self.tuning.elite.clamp(1, samples.len())];
+        let alpha = self.tuning.smoothing;
+        for (k, (old_mean, old_std)) in self.mean.iter_mut().zip(&mut self.std).enumerate() {
+            let mean = elite.iter().map(|&i| samples[i][k]).sum::<f32>() / elite.len() as f32;
+            let var = elite.iter().map(|&i| (samples[i][k] - mean).powi(2)).sum::<f32>() / elite.len() as f32;
+            *old_mean = alpha * mean + (1.0 - alpha) * *old_mean;
+            *old_std = (alpha * var.sqrt() + (1.0 - alpha) * *old_std).max(self.tuning.min_std);
+        }
+        let generation = Generation {
+            index: self.generation,
+            center: scores[0],
+            best: scores[order[0]],
+            average: scores.iter().map(Fitness::total).sum::<f64>() / scores.len() as f64,
+            mean: self.mean(),
+        };
+        self.generation += 1;
+        generation
+    }
+}
+
+/// 按可用的 CPU 核数分组并行评估
+fn evaluate_all<F>(configs: &[StrategyConfig], fitness: &F) -> Vec<Fitness>
+where
+    F: Fn(&StrategyConfig) -> Fitness + Sync,
+{
+    let workers = thread::available_parallelism().map_or(1, |n| n.get());
+    let chunk = configs.len().div_ceil(workers).max(1);
+    thread::scope(|scope| {
+        let handles: Vec<_> = configs
+            .chunks(chunk)
+            .map(|group| scope.spawn(move || group.iter().map(fitness).collect::<Vec<Fitness>>()))
+            .collect();
+        handles.into_iter().flat_map(|handle| handle.join().expect("evaluation thread panicked")).collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 测试1：击败的对手比例，同分计半个
+    #[test]
+    fn test_beaten_share() {
+        let result = MatchResult {
+            seed: 0,
+            scores: vec![5, 3, 5, 7],
+            alive: vec![true; 4],
+            dead_round: vec![100; 4],
+            time_ms: vec![0.0; 4],
+            timeouts: vec![0; 4],
+        };
+        assert_eq!(beaten_share(&result, 0), 0.5);
+        assert_eq!(beaten_share(&result, 3), 1.0);
+        assert_eq!(beaten_share(&result, 1), 0.0);
+    }
+
+    // 测试2：用人工构造的成绩函数检验分布向最优点收敛，且只改动指定的参数
+    #[test]
+    fn test_converges_on_synthetic_fitness() {
+        let tuning = TuningConfig { keys: vec!["score_weight".to_string()], population: 12, elite: 3, ..TuningConfig::default() };
+        let mut cem = CrossEntropy::new(tuning, StrategyConfig::default()).unwrap();
+        let target = |config: &StrategyConfig| {
+            let closeness = 1.0 / (1.0 + (config.score_weight as f64 - 14.0).abs());
+            Fitness { duel: closeness, melee: closeness }
+        };
+        for _ in 0..30 {
+            cem.step(target);
+        }
+        let mean = cem.mean();
+        assert!((mean.score_weight - 14.0).abs() < 0.5, "{}", mean.score_weight);
+        assert_eq!(StrategyConfig { score_weight: 10.0, ..mean }, StrategyConfig::default());
+
+        let unknown = TuningConfig { keys: vec!["unknown".to_string()], ..TuningConfig::default() };
+        assert!(CrossEntropy::new(unknown, StrategyConfig::default()).is_none());
+    }
+}